@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use serde_json::Value;
 
-use tidy_json::sort::sort;
+use tidy_json::sort::{sort, SortOptions};
 use tidy_json::SortOrder;
 
 /// Generate a flat JSON object with n keys
@@ -31,12 +31,13 @@ fn generate_nested_json(depth: usize, keys_per_level: usize) -> Value {
 /// Benchmark sorting by JSON size (number of keys)
 fn bench_by_size(c: &mut Criterion) {
     let mut group = c.benchmark_group("sort_by_size");
+    let options = SortOptions::new(SortOrder::AlphabeticalAsc);
 
     for size in [10, 100, 500, 1000] {
         let json = generate_flat_json(size);
 
         group.bench_with_input(BenchmarkId::new("keys", size), &json, |b, json| {
-            b.iter(|| sort(black_box(json), &SortOrder::AlphabeticalAsc, 0, None))
+            b.iter(|| sort(black_box(json), &options))
         });
     }
 
@@ -46,12 +47,13 @@ fn bench_by_size(c: &mut Criterion) {
 /// Benchmark sorting by nesting depth
 fn bench_by_depth(c: &mut Criterion) {
     let mut group = c.benchmark_group("sort_by_depth");
+    let options = SortOptions::new(SortOrder::AlphabeticalAsc);
 
     for depth in [1, 3, 5, 7] {
         let json = generate_nested_json(depth, 5);
 
         group.bench_with_input(BenchmarkId::new("depth", depth), &json, |b, json| {
-            b.iter(|| sort(black_box(json), &SortOrder::AlphabeticalAsc, 0, None))
+            b.iter(|| sort(black_box(json), &options))
         });
     }
 
@@ -72,8 +74,9 @@ fn bench_by_sort_order(c: &mut Criterion) {
     ];
 
     for (name, order) in orders {
+        let options = SortOptions::new(order);
         group.bench_with_input(BenchmarkId::new("order", name), &json, |b, json| {
-            b.iter(|| sort(black_box(json), &order, 0, None))
+            b.iter(|| sort(black_box(json), &options))
         });
     }
 
@@ -0,0 +1,166 @@
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::cst::Node;
+use crate::sort::{self, SortOptions};
+use crate::{Collation, SortOrder};
+
+/// A JSON5 number such as `NaN` or `Infinity`, which has no canonical form.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("`{0}` is not a finite number")]
+pub struct Error(pub String);
+
+/// Writes `node` in the RFC 8785 JSON Canonicalization Scheme: keys sorted
+/// by UTF-16 code units at every depth, numbers serialized as ECMAScript
+/// does, minimal string escapes and no whitespace or comments. Arrays keep
+/// their order.
+pub fn jcs(node: &Node) -> Result<String, Error> {
+    let options = SortOptions {
+        collation: Collation::Utf16,
+        ..SortOptions::new(SortOrder::AlphabeticalAsc)
+    };
+    let mut out = String::new();
+    write(&sort::sort(node, &options), &mut out)?;
+    Ok(out)
+}
+
+fn write(node: &Node, out: &mut String) -> Result<(), Error> {
+    match node {
+        Node::Object(object) => {
+            out.push('{');
+            for (index, member) in object.members.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(member.key.name.clone()).to_string());
+                out.push(':');
+                write(&member.value, out)?;
+            }
+            out.push('}');
+        }
+        Node::Array(array) => {
+            out.push('[');
+            for (index, item) in array.items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write(&item.value, out)?;
+            }
+            out.push(']');
+        }
+        // `Infinity` and `NaN` are parsed as null and rejected here. The
+        // literal is read again because serde_json may round the last digit
+        // of a float differently.
+        Node::Scalar(scalar)
+            if scalar.value.is_number() || scalar.value.is_null() && scalar.raw != "null" =>
+        {
+            match scalar.raw.parse::<f64>().ok().or(scalar.value.as_f64()) {
+                Some(number) if number.is_finite() => {
+                    out.push_str(ryu_js::Buffer::new().format_finite(number))
+                }
+                _ => return Err(Error(scalar.raw.clone())),
+            }
+        }
+        // serde_json escapes only `"`, `\` and control characters, in the
+        // short form where one exists, as RFC 8785 requires.
+        Node::Scalar(scalar) => out.push_str(&scalar.value.to_string()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst;
+
+    fn canonicalize(source: &str) -> Result<String, Error> {
+        jcs(&cst::parse(source).unwrap().root)
+    }
+
+    #[test]
+    fn test_rfc8785_example() {
+        let source = r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50,
+              2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#;
+        assert_eq!(
+            canonicalize(source).unwrap(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn test_rfc8785_key_order() {
+        let source = r#"{
+  "€": "Euro Sign",
+  "\r": "Carriage Return",
+  "דּ": "Hebrew Letter Dalet With Dagesh",
+  "1": "One",
+  "😀": "Emoji: Grinning Face",
+  "\u0080": "Control",
+  "ö": "Latin Small Letter O With Diaeresis"
+}"#;
+        let canonical: Value = serde_json::from_str(&canonicalize(source).unwrap()).unwrap();
+        let keys: Vec<&str> = canonical
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|value| value.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "Carriage Return",
+                "One",
+                "Control",
+                "Latin Small Letter O With Diaeresis",
+                "Euro Sign",
+                "Emoji: Grinning Face",
+                "Hebrew Letter Dalet With Dagesh"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rfc8785_numbers() {
+        let vectors: [(u64, &str); 24] = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0x41b3de4355555556, "333333333.3333334"),
+            (0x41b3de4355555557, "333333333.33333343"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ];
+        for (bits, expected) in vectors {
+            // Rust prints the shortest representation that reads back exactly.
+            let source = format!("[{:e}]", f64::from_bits(bits));
+            assert_eq!(canonicalize(&source).unwrap(), format!("[{expected}]"));
+        }
+
+        assert_eq!(
+            canonicalize("[-Infinity]"),
+            Err(Error("-Infinity".to_string()))
+        );
+        assert_eq!(canonicalize("[NaN]"), Err(Error("NaN".to_string())));
+    }
+}
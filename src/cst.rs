@@ -0,0 +1,1142 @@
+use serde::de::IgnoredAny;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+use crate::sort::{self, Sortable};
+use crate::{DuplicateKeys, StringEscapes, Style, TrailingCommas};
+
+/// A syntax error with the 1-based position where it was found.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{message} at line {line} column {column}")]
+pub struct Error {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// How deeply objects and arrays may nest, as in serde_json. Deeper input is
+/// rejected rather than overflowing the stack while parsing, sorting or
+/// printing it.
+pub const MAX_DEPTH: usize = 128;
+
+/// A `//` or `/* */` comment exactly as written, delimiters included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String,
+}
+
+/// A key that appears more than once in the same object, at the 1-based
+/// position of the repeated occurrence.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("duplicate key `{name}` at line {line} column {column}")]
+pub struct DuplicateKey {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Every repeated key of a document resolved with [`DuplicateKeys::Error`],
+/// in source order.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+pub struct DuplicateKeysError(pub Vec<DuplicateKey>);
+
+/// A parsed document together with the comments surrounding its root value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub leading: Vec<Comment>,
+    pub root: Node,
+    pub trailing: Vec<Comment>,
+    /// Every repeated key, in source order. Objects keep all of their
+    /// members until [`Document::resolve_duplicates`] is called.
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+impl Document {
+    /// Applies `policy` to the repeated keys of every object, failing with
+    /// all of them for [`DuplicateKeys::Error`].
+    pub fn resolve_duplicates(
+        &mut self,
+        policy: &DuplicateKeys,
+    ) -> std::result::Result<(), DuplicateKeysError> {
+        match policy {
+            _ if self.duplicates.is_empty() => Ok(()),
+            DuplicateKeys::Error => Err(DuplicateKeysError(self.duplicates.clone())),
+            policy => {
+                resolve_duplicates(&mut self.root, policy);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn resolve_duplicates(node: &mut Node, policy: &DuplicateKeys) {
+    match node {
+        Node::Object(object) => {
+            let mut index: HashMap<String, usize> = HashMap::new();
+            let mut groups: Vec<Vec<Member>> = Vec::new();
+            for member in object.members.drain(..) {
+                match index.get(&member.key.name) {
+                    Some(&group) => groups[group].push(member),
+                    None => {
+                        index.insert(member.key.name.clone(), groups.len());
+                        groups.push(vec![member]);
+                    }
+                }
+            }
+            // Each group takes the place of the key's first occurrence.
+            for mut group in groups {
+                match policy {
+                    DuplicateKeys::KeepFirst => group.truncate(1),
+                    DuplicateKeys::KeepLast => {
+                        group.drain(..group.len() - 1);
+                    }
+                    DuplicateKeys::KeepAllAdjacent | DuplicateKeys::Error => {}
+                }
+                object.members.extend(group);
+            }
+            for member in &mut object.members {
+                resolve_duplicates(&mut member.value, policy);
+            }
+        }
+        Node::Array(array) => {
+            for item in &mut array.items {
+                resolve_duplicates(&mut item.value, policy);
+            }
+        }
+        Node::Scalar(_) => {}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Object(Object),
+    Array(Array),
+    Scalar(Scalar),
+}
+
+/// A string, number, boolean or null token and the value it decodes to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scalar {
+    pub raw: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    pub members: Vec<Member>,
+    /// Comments after the last member, before the closing brace.
+    pub dangling: Vec<Comment>,
+    /// Whether the last member was followed by a comma.
+    pub trailing_comma: bool,
+}
+
+/// An object member. `leading` comments sit on the lines above it and
+/// `trailing` comments on the same line after its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub leading: Vec<Comment>,
+    pub key: Key,
+    pub value: Node,
+    pub trailing: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub raw: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array {
+    pub items: Vec<Item>,
+    /// Comments after the last item, before the closing bracket.
+    pub dangling: Vec<Comment>,
+    /// Whether the last item was followed by a comma.
+    pub trailing_comma: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub leading: Vec<Comment>,
+    pub value: Node,
+    pub trailing: Vec<Comment>,
+}
+
+impl Node {
+    /// Converts the tree into a `serde_json::Value`, dropping all comments.
+    pub fn to_value(&self) -> Value {
+        match self {
+            Node::Object(object) => Value::Object(
+                object
+                    .members
+                    .iter()
+                    .map(|member| (member.key.name.clone(), member.value.to_value()))
+                    .collect(),
+            ),
+            Node::Array(array) => Value::Array(
+                array
+                    .items
+                    .iter()
+                    .map(|item| item.value.to_value())
+                    .collect(),
+            ),
+            Node::Scalar(scalar) => scalar.value.clone(),
+        }
+    }
+}
+
+impl Sortable for Node {
+    type Member = Member;
+    type Item = Item;
+
+    fn take_members(&mut self) -> Option<Vec<Member>> {
+        match self {
+            Node::Object(object) => Some(std::mem::take(&mut object.members)),
+            _ => None,
+        }
+    }
+
+    fn set_members(&mut self, members: Vec<Member>) {
+        if let Node::Object(object) = self {
+            object.members = members;
+        }
+    }
+
+    fn member_key(member: &Member) -> &str {
+        &member.key.name
+    }
+
+    fn member_line(member: &Member, options: &PrintOptions, level: usize) -> String {
+        let mut printer = Printer {
+            out: options.indent.repeat(level),
+            options,
+            reserve: 0,
+        };
+        printer.member(member, level);
+        match printer.out.find('\n') {
+            Some(end) => printer.out[..end].to_string(),
+            None => printer.out,
+        }
+    }
+
+    fn member_value(member: &Member) -> &Node {
+        &member.value
+    }
+
+    fn member_value_mut(member: &mut Member) -> &mut Node {
+        &mut member.value
+    }
+
+    fn take_items(&mut self) -> Option<Vec<Item>> {
+        match self {
+            Node::Array(array) => Some(std::mem::take(&mut array.items)),
+            _ => None,
+        }
+    }
+
+    fn set_items(&mut self, items: Vec<Item>) {
+        if let Node::Array(array) = self {
+            array.items = items;
+        }
+    }
+
+    fn item_value(item: &Item) -> &Node {
+        &item.value
+    }
+
+    fn item_value_mut(item: &mut Item) -> &mut Node {
+        &mut item.value
+    }
+
+    fn to_value(&self) -> Cow<'_, Value> {
+        Cow::Owned(Node::to_value(self))
+    }
+
+    fn dedupe_key(&self) -> String {
+        match self {
+            // Keyed by the token, so big integers and non-finite JSON5
+            // numbers are not confused by their lossy values.
+            Node::Scalar(Scalar {
+                raw,
+                value: Value::Number(_) | Value::Null,
+            }) if raw != "null" => sort::number_key(raw),
+            Node::Scalar(scalar) => scalar.value.to_string(),
+            Node::Array(array) => {
+                sort::array_key(array.items.iter().map(|item| item.value.dedupe_key()))
+            }
+            Node::Object(object) => sort::object_key(
+                object
+                    .members
+                    .iter()
+                    .map(|member| (member.key.name.as_str(), member.value.dedupe_key())),
+            ),
+        }
+    }
+}
+
+/// Parses JSON, JSONC or JSON5 source, keeping every comment attached to the
+/// member or item it belongs to.
+pub fn parse(source: &str) -> Result<Document> {
+    let mut parser = Parser {
+        source,
+        pos: 0,
+        depth: 0,
+        duplicates: Vec::new(),
+    };
+    let leading = parser.trivia()?.into_all();
+    let root = parser.value()?;
+    let trailing = parser.trivia()?.into_all();
+    if parser.pos < source.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    Ok(Document {
+        leading,
+        root,
+        trailing,
+        duplicates: parser.duplicates,
+    })
+}
+
+/// Comments between two tokens, split at the first line break.
+struct Trivia {
+    same_line: Vec<Comment>,
+    next_lines: Vec<Comment>,
+}
+
+impl Trivia {
+    fn into_all(self) -> Vec<Comment> {
+        let mut all = self.same_line;
+        all.extend(self.next_lines);
+        all
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// How many objects and arrays enclose the current position.
+    depth: usize,
+    duplicates: Vec<DuplicateKey>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> Error {
+        let before = &self.source[..pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        Error {
+            message: message.to_string(),
+            line,
+            column,
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.peek() == Some(expected) {
+            self.bump();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{expected}`")))
+        }
+    }
+
+    fn trivia(&mut self) -> Result<Trivia> {
+        let mut trivia = Trivia {
+            same_line: Vec::new(),
+            next_lines: Vec::new(),
+        };
+        let mut seen_newline = false;
+
+        loop {
+            let rest = self.rest();
+            let text = if rest.starts_with("//") {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                self.pos += end;
+                rest[..end].trim_end()
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                let end = body
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?
+                    + 4;
+                self.pos += end;
+                &rest[..end]
+            } else {
+                match self.peek() {
+                    Some(ch) if ch.is_whitespace() || ch == '\u{feff}' => {
+                        seen_newline |= ch == '\n';
+                        self.bump();
+                        continue;
+                    }
+                    _ => return Ok(trivia),
+                }
+            };
+
+            let comment = Comment {
+                text: text.to_string(),
+            };
+            if seen_newline {
+                trivia.next_lines.push(comment);
+            } else {
+                trivia.same_line.push(comment);
+            }
+        }
+    }
+
+    /// Consumes the comments and comma following an element. Comments on the
+    /// element's line are returned; the rest are queued in `pending` for the
+    /// next element. The flag reports whether a comma was found.
+    fn separator(&mut self, pending: &mut Vec<Comment>) -> Result<(Vec<Comment>, bool)> {
+        let after_value = self.trivia()?;
+        let mut trailing = after_value.same_line;
+        pending.extend(after_value.next_lines);
+
+        if self.peek() != Some(',') {
+            return Ok((trailing, false));
+        }
+        self.bump();
+
+        let after_comma = self.trivia()?;
+        if pending.is_empty() {
+            trailing.extend(after_comma.same_line);
+        } else {
+            pending.extend(after_comma.same_line);
+        }
+        pending.extend(after_comma.next_lines);
+
+        Ok((trailing, true))
+    }
+
+    fn value(&mut self) -> Result<Node> {
+        match self.peek() {
+            Some(ch @ ('{' | '[')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("nesting too deep"));
+                }
+                self.depth += 1;
+                let node = match ch {
+                    '{' => self.object().map(Node::Object),
+                    _ => self.array().map(Node::Array),
+                };
+                self.depth -= 1;
+                node
+            }
+            Some('"' | '\'') => {
+                let (raw, value) = self.string()?;
+                Ok(Node::Scalar(Scalar {
+                    raw,
+                    value: Value::String(value),
+                }))
+            }
+            Some(ch) if ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.') => self.number(),
+            Some(ch) if is_identifier_start(ch) => self.literal(),
+            _ => Err(self.error("expected value")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Object> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        let mut names = HashSet::new();
+        let mut pending = self.trivia()?.into_all();
+        let mut trailing_comma = false;
+
+        while self.peek() != Some('}') {
+            let start = self.pos;
+            let key = self.key()?;
+            if !names.insert(key.name.clone()) {
+                let Error { line, column, .. } = self.error_at(start, "duplicate key");
+                self.duplicates.push(DuplicateKey {
+                    name: key.name.clone(),
+                    line,
+                    column,
+                });
+            }
+            pending.extend(self.trivia()?.into_all());
+            self.expect(':')?;
+            pending.extend(self.trivia()?.into_all());
+            let value = self.value()?;
+
+            let leading = std::mem::take(&mut pending);
+            let (trailing, more) = self.separator(&mut pending)?;
+            members.push(Member {
+                leading,
+                key,
+                value,
+                trailing,
+            });
+            trailing_comma = more;
+
+            if !more {
+                if self.peek() != Some('}') {
+                    return Err(self.error("expected `,` or `}`"));
+                }
+                break;
+            }
+        }
+        self.bump();
+
+        Ok(Object {
+            members,
+            dangling: pending,
+            trailing_comma,
+        })
+    }
+
+    fn array(&mut self) -> Result<Array> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        let mut pending = self.trivia()?.into_all();
+        let mut trailing_comma = false;
+
+        while self.peek() != Some(']') {
+            let value = self.value()?;
+
+            let leading = std::mem::take(&mut pending);
+            let (trailing, more) = self.separator(&mut pending)?;
+            items.push(Item {
+                leading,
+                value,
+                trailing,
+            });
+            trailing_comma = more;
+
+            if !more {
+                if self.peek() != Some(']') {
+                    return Err(self.error("expected `,` or `]`"));
+                }
+                break;
+            }
+        }
+        self.bump();
+
+        Ok(Array {
+            items,
+            dangling: pending,
+            trailing_comma,
+        })
+    }
+
+    fn key(&mut self) -> Result<Key> {
+        match self.peek() {
+            Some('"' | '\'') => {
+                let (raw, name) = self.string()?;
+                Ok(Key { raw, name })
+            }
+            Some(ch) if is_identifier_start(ch) => {
+                let raw = self.identifier();
+                Ok(Key {
+                    raw: raw.to_string(),
+                    name: raw.to_string(),
+                })
+            }
+            _ => Err(self.error("expected key")),
+        }
+    }
+
+    fn string(&mut self) -> Result<(String, String)> {
+        let start = self.pos;
+        let quote = self.bump();
+        loop {
+            match self.bump() {
+                None => return Err(self.error_at(start, "unterminated string")),
+                Some('\\') => {
+                    self.bump();
+                }
+                ch if ch == quote => break,
+                Some(_) => {}
+            }
+        }
+
+        let raw = &self.source[start..self.pos];
+        let value = serde_json::from_str::<String>(raw)
+            .or_else(|_| json5::from_str::<String>(raw))
+            .map_err(|_| self.error_at(start, "invalid string"))?;
+        Ok((raw.to_string(), value))
+    }
+
+    fn number(&mut self) -> Result<Node> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'))
+        {
+            self.bump();
+        }
+        self.scalar(start, "invalid number")
+    }
+
+    fn literal(&mut self) -> Result<Node> {
+        let start = self.pos;
+        match self.identifier() {
+            "true" | "false" | "null" | "Infinity" | "NaN" => self.scalar(start, "invalid literal"),
+            _ => Err(self.error_at(start, "expected value")),
+        }
+    }
+
+    fn scalar(&self, start: usize, message: &str) -> Result<Node> {
+        let raw = &self.source[start..self.pos];
+        // JSON has no infinities or NaN, so they sort and convert as null.
+        if matches!(raw.trim_start_matches(['+', '-']), "Infinity" | "NaN") {
+            return Ok(Node::Scalar(Scalar {
+                raw: raw.to_string(),
+                value: Value::Null,
+            }));
+        }
+        let value = serde_json::from_str::<Value>(raw)
+            .or_else(|_| json5::from_str::<Value>(raw))
+            .map_err(|_| self.error_at(start, message))?;
+        Ok(Node::Scalar(Scalar {
+            raw: raw.to_string(),
+            value,
+        }))
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(is_identifier_char) {
+            self.bump();
+        }
+        &self.source[start..self.pos]
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || matches!(ch, '_' | '$')
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '$')
+}
+
+/// Settings for [`print`].
+#[derive(Debug, Clone)]
+pub struct PrintOptions {
+    /// [`Style::Compact`] ignores the indent, print width and trailing commas
+    /// and drops every comment.
+    pub style: Style,
+    /// Written once per nesting level.
+    pub indent: String,
+    pub escapes: StringEscapes,
+    /// Keeps JSON5 syntax: unquoted keys, single quotes and number forms
+    /// such as hex or `Infinity` are written as they were typed.
+    pub json5: bool,
+    pub trailing_commas: TrailingCommas,
+    /// Objects and arrays that fit on one line within this many characters
+    /// are written inline; they are always expanded when unset.
+    pub print_width: Option<usize>,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions {
+            style: Style::default(),
+            indent: "  ".to_string(),
+            escapes: StringEscapes::default(),
+            json5: false,
+            trailing_commas: TrailingCommas::default(),
+            print_width: None,
+        }
+    }
+}
+
+/// Pretty-prints a document with one member or item per line, writing each
+/// comment back next to the element it was attached to.
+pub fn print(document: &Document, options: &PrintOptions) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        options,
+        reserve: 0,
+    };
+    if options.style == Style::Compact {
+        printer.compact(&document.root);
+        return printer.out;
+    }
+    for comment in &document.leading {
+        printer.out.push_str(&comment.text);
+        printer.out.push('\n');
+    }
+    printer.node(&document.root, 0);
+    for comment in &document.trailing {
+        printer.out.push('\n');
+        printer.out.push_str(&comment.text);
+    }
+    printer.out
+}
+
+struct Printer<'a> {
+    out: String,
+    options: &'a PrintOptions,
+    /// Characters that will follow the next node on its line, such as a
+    /// comma, counted when deciding whether it fits.
+    reserve: usize,
+}
+
+impl Printer<'_> {
+    fn newline(&mut self, level: usize) {
+        self.out.push('\n');
+        for _ in 0..level {
+            self.out.push_str(&self.options.indent);
+        }
+    }
+
+    fn node(&mut self, node: &Node, level: usize) {
+        let reserve = std::mem::take(&mut self.reserve);
+        if matches!(node, Node::Object(_) | Node::Array(_)) && self.try_inline(node, reserve) {
+            return;
+        }
+        match node {
+            Node::Object(object) => {
+                self.out.push('{');
+                for (index, member) in object.members.iter().enumerate() {
+                    let comma = index + 1 < object.members.len()
+                        || self.trailing_comma(object.trailing_comma);
+                    self.entry(&member.leading, &member.trailing, comma, level + 1, |p| {
+                        p.member(member, level + 1)
+                    });
+                }
+                self.close(&object.dangling, object.members.is_empty(), level, '}');
+            }
+            Node::Array(array) => {
+                self.out.push('[');
+                for (index, item) in array.items.iter().enumerate() {
+                    let comma =
+                        index + 1 < array.items.len() || self.trailing_comma(array.trailing_comma);
+                    self.entry(&item.leading, &item.trailing, comma, level + 1, |p| {
+                        p.node(&item.value, level + 1);
+                    });
+                }
+                self.close(&array.dangling, array.items.is_empty(), level, ']');
+            }
+            Node::Scalar(Scalar {
+                raw,
+                value: Value::String(value),
+            }) => self.string(raw, value),
+            // Numbers are written as they were typed so that no digit is lost;
+            // outside JSON5 output, JSON5-only forms such as hex become JSON.
+            Node::Scalar(scalar) if self.options.json5 || is_json_number(&scalar.raw) => {
+                self.out.push_str(&scalar.raw)
+            }
+            Node::Scalar(scalar) => self.out.push_str(&scalar.value.to_string()),
+        }
+    }
+
+    /// Writes `node` without any whitespace or comments.
+    fn compact(&mut self, node: &Node) {
+        match node {
+            Node::Object(object) => {
+                self.out.push('{');
+                for (index, member) in object.members.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push(',');
+                    }
+                    self.string(&member.key.raw, &member.key.name);
+                    self.out.push(':');
+                    self.compact(&member.value);
+                }
+                self.out.push('}');
+            }
+            Node::Array(array) => {
+                self.out.push('[');
+                for (index, item) in array.items.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push(',');
+                    }
+                    self.compact(&item.value);
+                }
+                self.out.push(']');
+            }
+            Node::Scalar(_) => self.node(node, 0),
+        }
+    }
+
+    /// Writes an object or array on the current line if it holds no comments
+    /// and fits within the print width, followed by `reserve` characters.
+    fn try_inline(&mut self, node: &Node, reserve: usize) -> bool {
+        let Some(width) = self.options.print_width else {
+            return false;
+        };
+        let column = self.out.rsplit('\n').next().unwrap_or("").chars().count();
+        let Some(limit) = width.checked_sub(column + reserve) else {
+            return false;
+        };
+        let mut inline = Printer {
+            out: String::new(),
+            options: self.options,
+            reserve: 0,
+        };
+        if !inline.inline(node, limit) {
+            return false;
+        }
+        self.out.push_str(&inline.out);
+        true
+    }
+
+    /// Writes `node` on one line, giving up on the first comment or once the
+    /// line is longer than `limit` characters.
+    fn inline(&mut self, node: &Node, limit: usize) -> bool {
+        match node {
+            Node::Object(object) if object.members.is_empty() => {
+                self.out.push_str("{}");
+                object.dangling.is_empty()
+            }
+            Node::Object(object) => {
+                self.out.push_str("{ ");
+                for (index, member) in object.members.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.string(&member.key.raw, &member.key.name);
+                    self.out.push_str(": ");
+                    if !member.leading.is_empty()
+                        || !member.trailing.is_empty()
+                        || !self.inline(&member.value, limit)
+                        || self.out.chars().count() > limit
+                    {
+                        return false;
+                    }
+                }
+                self.out.push_str(" }");
+                object.dangling.is_empty() && self.out.chars().count() <= limit
+            }
+            Node::Array(array) => {
+                self.out.push('[');
+                for (index, item) in array.items.iter().enumerate() {
+                    if index > 0 {
+                        self.out.push_str(", ");
+                    }
+                    if !item.leading.is_empty()
+                        || !item.trailing.is_empty()
+                        || !self.inline(&item.value, limit)
+                        || self.out.chars().count() > limit
+                    {
+                        return false;
+                    }
+                }
+                self.out.push(']');
+                array.dangling.is_empty() && self.out.chars().count() <= limit
+            }
+            Node::Scalar(_) => {
+                self.node(node, 0);
+                self.out.chars().count() <= limit
+            }
+        }
+    }
+
+    /// Whether the last element of a container that `had` a trailing comma
+    /// in the input gets one.
+    fn trailing_comma(&self, had: bool) -> bool {
+        match self.options.trailing_commas {
+            TrailingCommas::Preserve => had,
+            TrailingCommas::Always => true,
+            TrailingCommas::Never => false,
+        }
+    }
+
+    fn member(&mut self, member: &Member, level: usize) {
+        self.string(&member.key.raw, &member.key.name);
+        self.out.push_str(": ");
+        self.node(&member.value, level);
+    }
+
+    fn string(&mut self, raw: &str, value: &str) {
+        write_string(&mut self.out, raw, value, self.options);
+    }
+
+    fn entry(
+        &mut self,
+        leading: &[Comment],
+        trailing: &[Comment],
+        comma: bool,
+        level: usize,
+        body: impl FnOnce(&mut Self),
+    ) {
+        self.newline(level);
+        for comment in leading {
+            self.out.push_str(&comment.text);
+            self.newline(level);
+        }
+        self.reserve = usize::from(comma);
+        body(self);
+        if comma {
+            self.out.push(',');
+        }
+        for comment in trailing {
+            self.out.push(' ');
+            self.out.push_str(&comment.text);
+        }
+    }
+
+    fn close(&mut self, dangling: &[Comment], empty: bool, level: usize, close: char) {
+        for comment in dangling {
+            self.newline(level + 1);
+            self.out.push_str(&comment.text);
+        }
+        if !empty || !dangling.is_empty() {
+            self.newline(level);
+        }
+        self.out.push(close);
+    }
+}
+
+/// Writes a string token as it was typed, unless escapes are normalized or
+/// the token is not valid JSON (e.g. a single-quoted JSON5 string).
+pub(crate) fn write_string(out: &mut String, raw: &str, value: &str, options: &PrintOptions) {
+    let json5 = options.json5;
+    let identifier = json5 && !raw.starts_with(['"', '\'']);
+    let preserve = identifier
+        || options.escapes != StringEscapes::Normalize
+            && (json5 || raw.starts_with('"') && serde_json::from_str::<IgnoredAny>(raw).is_ok());
+    let text = if preserve {
+        Cow::Borrowed(raw)
+    } else {
+        Cow::Owned(json_string(value))
+    };
+    if options.escapes == StringEscapes::Ascii {
+        escape_non_ascii(&text, out);
+    } else {
+        out.push_str(&text);
+    }
+}
+
+pub(crate) fn is_json_number(raw: &str) -> bool {
+    fn digits(s: &str) -> (&str, &str) {
+        s.split_at(s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len()))
+    }
+
+    let (int, rest) = digits(raw.strip_prefix('-').unwrap_or(raw));
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => match digits(fraction) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+    match rest.strip_prefix(['e', 'E']) {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            matches!(digits(exponent), (digits, "") if !digits.is_empty())
+        }
+        None => rest.is_empty(),
+    }
+}
+
+fn escape_non_ascii(text: &str, out: &mut String) {
+    for ch in text.chars() {
+        if ch.is_ascii() {
+            out.push(ch);
+        } else {
+            for unit in ch.encode_utf16(&mut [0; 2]) {
+                out.push_str(&format!("\\u{unit:04x}"));
+            }
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(source: &str) -> String {
+        print(&parse(source).unwrap(), &PrintOptions::default())
+    }
+
+    #[test]
+    fn test_print_matches_pretty_json() {
+        let source = r#"{"b": [1, 2.5, {"c": null}], "a": "xé", "e": {}, "f": []}"#;
+        let value: Value = serde_json::from_str(source).unwrap();
+        assert_eq!(
+            round_trip(source),
+            serde_json::to_string_pretty(&value).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_number_literals_are_kept_exactly() {
+        let numbers = [
+            "1.0",
+            "1e3",
+            "1E+3",
+            "-0",
+            "0.1000",
+            "123456789012345678901234567890",
+            "3.14159265358979323846264338327950288",
+            "19.99",
+        ];
+        let source = format!("[{}]", numbers.join(", "));
+        let expected = format!("[\n  {}\n]", numbers.join(",\n  "));
+        assert_eq!(round_trip(&source), expected);
+    }
+
+    #[test]
+    fn test_is_json_number() {
+        for raw in ["0", "-0", "1.5", "1e3", "1E-3", "10.0e+10"] {
+            assert!(is_json_number(raw), "{raw}");
+        }
+        for raw in [
+            "01", "+1", ".5", "5.", "0x1F", "1e", "Infinity", "true", "1_0",
+        ] {
+            assert!(!is_json_number(raw), "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let source = "{\n  \"a\": 1,\n  \"b\": {\"c\": 1, \"c\": 2},\n  \"a\": 2\n}";
+        let resolve = |policy| {
+            let mut document = parse(source).unwrap();
+            document
+                .resolve_duplicates(&policy)
+                .map(|_| document.root.to_value().to_string())
+        };
+
+        assert_eq!(
+            parse(source).unwrap().duplicates,
+            [
+                DuplicateKey {
+                    name: "c".to_string(),
+                    line: 3,
+                    column: 17,
+                },
+                DuplicateKey {
+                    name: "a".to_string(),
+                    line: 4,
+                    column: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            resolve(DuplicateKeys::Error).unwrap_err().to_string(),
+            "duplicate key `c` at line 3 column 17, duplicate key `a` at line 4 column 3"
+        );
+        assert_eq!(
+            resolve(DuplicateKeys::KeepFirst).unwrap(),
+            r#"{"a":1,"b":{"c":1}}"#
+        );
+        assert_eq!(
+            resolve(DuplicateKeys::KeepLast).unwrap(),
+            r#"{"a":2,"b":{"c":2}}"#
+        );
+
+        let mut document = parse(source).unwrap();
+        document
+            .resolve_duplicates(&DuplicateKeys::KeepAllAdjacent)
+            .unwrap();
+        assert_eq!(
+            print(&document, &PrintOptions::default()),
+            "{\n  \"a\": 1,\n  \"a\": 2,\n  \"b\": {\n    \"c\": 1,\n    \"c\": 2\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn test_print_compact() {
+        let document =
+            parse("// header\n{\"a\": [1, {\"b\": null}], /* c */ \"c\": \"x y\",}").unwrap();
+        let options = PrintOptions {
+            style: Style::Compact,
+            trailing_commas: TrailingCommas::Always,
+            ..PrintOptions::default()
+        };
+        assert_eq!(
+            print(&document, &options),
+            r#"{"a":[1,{"b":null}],"c":"x y"}"#
+        );
+    }
+
+    #[test]
+    fn test_comments_are_attached_to_members() {
+        let source = r#"// header
+{
+  // above b
+  "b": 2, // after b
+  "a": [
+    1, // after one
+    // above two
+    2
+    // end of array
+  ],
+  /* block */ "c": 3
+  // end of object
+}"#;
+        let document = parse(source).unwrap();
+        let Node::Object(object) = &document.root else {
+            panic!("expected object");
+        };
+
+        assert_eq!(document.leading[0].text, "// header");
+        assert_eq!(object.members[0].leading[0].text, "// above b");
+        assert_eq!(object.members[0].trailing[0].text, "// after b");
+        assert_eq!(object.members[2].leading[0].text, "/* block */");
+        assert_eq!(object.dangling[0].text, "// end of object");
+
+        let Node::Array(array) = &object.members[1].value else {
+            panic!("expected array");
+        };
+        assert_eq!(array.items[0].trailing[0].text, "// after one");
+        assert_eq!(array.items[1].leading[0].text, "// above two");
+        assert_eq!(array.dangling[0].text, "// end of array");
+
+        assert_eq!(
+            round_trip(source),
+            source.replace("/* block */ ", "/* block */\n  ")
+        );
+    }
+
+    #[test]
+    fn test_parse_json5_syntax() {
+        let document = parse("{unquoted: 'single', hex: 0x1F, plus: +1, trailing: [1,],}").unwrap();
+        assert_eq!(
+            document.root.to_value(),
+            serde_json::json!({"unquoted": "single", "hex": 31, "plus": 1, "trailing": [1]})
+        );
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let err = parse("{\n  \"a\": 1\n  \"b\": 2\n}").unwrap_err();
+        assert_eq!(err.to_string(), "expected `,` or `}` at line 3 column 3");
+
+        let err = parse("{\"a\": /* open").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 7));
+    }
+
+    #[test]
+    fn test_parse_rejects_deep_nesting() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+
+        let err = parse(&nested(100_000)).unwrap_err();
+        assert_eq!(err.to_string(), "nesting too deep at line 1 column 129");
+        let err = parse(&format!("{{\n  \"a\": {}}}", nested(MAX_DEPTH))).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 135));
+    }
+}
@@ -0,0 +1,32 @@
+use similar::TextDiff;
+
+/// Renders a unified diff with three lines of context from `original` to
+/// `formatted`, labelled with `path` the way `git diff` does. Returns an
+/// empty string when both are equal.
+pub fn unified_diff(path: &str, original: &str, formatted: &str) -> String {
+    if original == formatted {
+        return String::new();
+    }
+    let path = path.strip_prefix("./").unwrap_or(path);
+    TextDiff::from_lines(original, formatted)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let original = "{\n  \"b\": 1,\n  \"a\": 2\n}";
+        let formatted = "{\n  \"a\": 2,\n  \"b\": 1\n}";
+        assert_eq!(
+            unified_diff("./sample.json", original, formatted),
+            "--- a/sample.json\n+++ b/sample.json\n@@ -1,4 +1,4 @@\n {\n-  \"b\": 1,\n-  \"a\": 2\n+  \"a\": 2,\n+  \"b\": 1\n }\n\\ No newline at end of file\n"
+        );
+        assert_eq!(unified_diff("sample.json", original, original), "");
+    }
+}
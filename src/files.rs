@@ -1,13 +1,20 @@
-use anyhow::*;
 use glob::{MatchOptions, Pattern};
 use ignore::WalkBuilder;
 use log::debug;
 use std::path::{Path, PathBuf};
 
+use crate::format::{Error, Result};
+use crate::preset::Preset;
+
 #[derive(Debug, PartialEq)]
 pub enum Extension {
     Json,
     Jsonc,
+    Json5,
+    /// JSON Lines, one document per line.
+    Jsonl,
+    /// Newline-delimited JSON, the same format as [`Extension::Jsonl`].
+    Ndjson,
 }
 
 impl Extension {
@@ -15,19 +22,41 @@ impl Extension {
         match self {
             Self::Json => "json",
             Self::Jsonc => "jsonc",
+            Self::Json5 => "json5",
+            Self::Jsonl => "jsonl",
+            Self::Ndjson => "ndjson",
         }
     }
+
+    /// Whether files with this extension hold one document per line.
+    pub fn is_lines(&self) -> bool {
+        matches!(self, Self::Jsonl | Self::Ndjson)
+    }
+
+    /// The extension of `path`, ignoring case.
+    pub fn of(path: &Path) -> Option<Extension> {
+        let ext = path.extension()?.to_str()?;
+        [
+            Self::Json,
+            Self::Jsonc,
+            Self::Json5,
+            Self::Jsonl,
+            Self::Ndjson,
+        ]
+        .into_iter()
+        .find(|known| ext.eq_ignore_ascii_case(known.as_str()))
+    }
 }
 
-fn create_patterns(patterns: Vec<PathBuf>) -> Result<Vec<Pattern>> {
+fn create_patterns(patterns: &[PathBuf]) -> Result<Vec<Pattern>> {
     patterns
-        .into_iter()
+        .iter()
         .map(|path| {
             let pattern_str = path
                 .to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid pattern path"))?
+                .ok_or_else(|| Error::Pattern(format!("{} is not valid UTF-8", path.display())))?
                 .trim_matches('"'); // Remove surrounding quotes if present
-            Pattern::new(pattern_str).map_err(|e| e.into())
+            Pattern::new(pattern_str).map_err(|e| Error::Pattern(format!("{pattern_str}: {e}")))
         })
         .collect()
 }
@@ -62,26 +91,33 @@ fn has_allowed_extension(path: &Path, allowed_extensions: &[Extension]) -> bool
         .unwrap_or(false)
 }
 
+/// Walks the working directory, honouring `.gitignore`, and returns the files
+/// with one of `allowed_extensions` that match an include pattern and no
+/// exclude pattern. Hidden files and directories are skipped, except for the
+/// dotfiles a preset recognises such as `.eslintrc.json`.
 pub fn list_files(
     include_patterns: &[PathBuf],
-    exclude_patterns: &Option<Vec<PathBuf>>,
+    exclude_patterns: &[PathBuf],
     allowed_extensions: Vec<Extension>,
 ) -> Result<Vec<PathBuf>> {
     let mut walk = WalkBuilder::new(".");
-    walk.hidden(true).ignore(true).git_global(true);
-
-    let include_patterns: Vec<Pattern> = create_patterns(include_patterns.to_vec())?;
-    let exclude_patterns: Vec<Pattern> = exclude_patterns
-        .clone()
-        .map(create_patterns)
-        .transpose()?
-        .unwrap_or_default();
+    walk.hidden(false)
+        .ignore(true)
+        .git_global(true)
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_name().to_string_lossy().starts_with('.')
+                || Preset::detect(entry.path()).is_some()
+        });
+
+    let include_patterns: Vec<Pattern> = create_patterns(include_patterns)?;
+    let exclude_patterns: Vec<Pattern> = create_patterns(exclude_patterns)?;
 
     let mut matching_files = Vec::new();
 
     for entry in walk.build() {
-        let entry = entry.context("Failed to read directory entry")?;
-        if entry.file_type().map_or(false, |ft| ft.is_file()) {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|ft| ft.is_file()) {
             let path = entry.path();
 
             debug!("Examining path {:?}.", path);
@@ -157,14 +193,23 @@ mod tests {
         File::create(subdir.join("test4.json")).unwrap();
         File::create(subdir.join("test5.jsonc")).unwrap();
 
-        let files =
-            list_files(&[PathBuf::from("**/*.json")], &None, vec![Extension::Json]).unwrap();
+        File::create(temp_path.join(".eslintrc.json")).unwrap();
+        File::create(temp_path.join(".hidden.json")).unwrap();
+        let hidden_dir = temp_path.join(".cache");
+        fs::create_dir(&hidden_dir).unwrap();
+        File::create(hidden_dir.join("test6.json")).unwrap();
+
+        let files = list_files(&[PathBuf::from("**/*.json")], &[], vec![Extension::Json]).unwrap();
 
         debug!("result {:?}, a {:?}", files, &temp_path.join("test1.json"));
 
-        assert_eq!(files.len(), 2);
+        assert_eq!(files.len(), 3);
         assert!(files.contains(&PathBuf::from("./test1.json")));
         assert!(files.contains(&PathBuf::from("./subdir/test4.json")));
+        assert!(files.contains(&PathBuf::from("./.eslintrc.json")));
+
+        assert!(!files.contains(&PathBuf::from("./.hidden.json")));
+        assert!(!files.contains(&PathBuf::from("./.cache/test6.json")));
 
         assert!(!files.contains(&PathBuf::from("./test2.jsonc")));
         assert!(!files.contains(&PathBuf::from("./test3.txt")));
@@ -177,7 +222,7 @@ mod tests {
 
         let files = list_files(
             &[PathBuf::from("./foo.json")],
-            &Some(vec![PathBuf::from("./test2bar.json")]),
+            &[PathBuf::from("./test2bar.json")],
             vec![Extension::Json],
         )
         .unwrap();
@@ -0,0 +1,753 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::canonical;
+use crate::cst::{self, DuplicateKey, PrintOptions};
+use crate::files::Extension;
+use crate::preset::Preset;
+use crate::sort::{self, SortOptions};
+use crate::{
+    Canonical, DuplicateKeys, EndOfLine, IndentStyle, StringEscapes, Style, TrailingCommas,
+};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse JSON content: {0}")]
+    Parse(#[from] cst::Error),
+    #[error("Repeated keys: {0}")]
+    DuplicateKeys(#[from] cst::DuplicateKeysError),
+    #[error("Invalid file pattern: {0}")]
+    Pattern(String),
+    #[error("Failed to read directory entry: {0}")]
+    Walk(#[from] ignore::Error),
+    #[error("Failed to read .editorconfig: {0}")]
+    EditorConfig(#[from] ec4rs::Error),
+    #[error("Failed to canonicalize: {0}")]
+    Canonical(#[from] canonical::Error),
+    #[error("Streaming does not support {0}")]
+    Stream(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Settings for [`format_str`] and [`format_file`], built the same way the
+/// CLI builds them from its flags and config file.
+///
+/// ```
+/// use tidy_json::{FormatOptions, IndentStyle, SortOrder};
+/// use tidy_json::sort::SortOptions;
+///
+/// let options = FormatOptions::new()
+///     .sort(SortOptions::new(SortOrder::AlphabeticalDesc))
+///     .indent(1)
+///     .indent_style(IndentStyle::Tabs);
+/// let outcome = tidy_json::format_str(r#"{"a": 1, "b": 2}"#, &options).unwrap();
+/// assert_eq!(outcome.output, "{\n\t\"b\": 2,\n\t\"a\": 1\n}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    sort: SortOptions,
+    preset: Option<Preset>,
+    pub(crate) canonical: Option<Canonical>,
+    style: Style,
+    indent: Option<usize>,
+    indent_style: Option<IndentStyle>,
+    escapes: StringEscapes,
+    trailing_commas: TrailingCommas,
+    pub(crate) end_of_line: Option<EndOfLine>,
+    pub(crate) insert_final_newline: Option<bool>,
+    editorconfig: bool,
+    pub(crate) print_width: Option<usize>,
+    pub(crate) duplicate_keys: DuplicateKeys,
+}
+
+/// The result of formatting one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOutcome {
+    /// The formatted document, exactly as the CLI would write it.
+    pub output: String,
+    /// Whether `output` differs from the input.
+    pub changed: bool,
+    /// Keys that were repeated in the input, resolved by the duplicate-key
+    /// policy.
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+impl FormatOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How keys, arrays and nested values are ordered.
+    pub fn sort(mut self, sort: SortOptions) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Layers a preset under the sort options; [`Preset::Auto`] only applies
+    /// to [`format_file`], which knows the file name.
+    pub fn preset(mut self, preset: impl Into<Option<Preset>>) -> Self {
+        self.preset = preset.into();
+        self
+    }
+
+    /// Writes a canonical form instead, which fixes the key order and layout
+    /// and so ignores every other setting.
+    pub fn canonical(mut self, canonical: impl Into<Option<Canonical>>) -> Self {
+        self.canonical = canonical.into();
+        self
+    }
+
+    /// Pretty-printed or minified output; pretty by default.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Number of indent characters per level; the input's indent is kept
+    /// when unset.
+    pub fn indent(mut self, indent: impl Into<Option<usize>>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    pub fn indent_style(mut self, indent_style: impl Into<Option<IndentStyle>>) -> Self {
+        self.indent_style = indent_style.into();
+        self
+    }
+
+    /// How string tokens are written; they are kept as typed by default.
+    pub fn escapes(mut self, escapes: StringEscapes) -> Self {
+        self.escapes = escapes;
+        self
+    }
+
+    /// Keeps objects and arrays on one line when they fit within this many
+    /// characters; by default every non-empty one is expanded.
+    pub fn print_width(mut self, print_width: impl Into<Option<usize>>) -> Self {
+        self.print_width = print_width.into();
+        self
+    }
+
+    /// Whether objects and arrays in `.jsonc` and `.json5` files end with a
+    /// comma; by default they never do. Plain JSON never gets them.
+    pub fn trailing_commas(mut self, trailing_commas: TrailingCommas) -> Self {
+        self.trailing_commas = trailing_commas;
+        self
+    }
+
+    /// The line break to write; the input's first line break is kept when
+    /// unset.
+    pub fn end_of_line(mut self, end_of_line: impl Into<Option<EndOfLine>>) -> Self {
+        self.end_of_line = end_of_line.into();
+        self
+    }
+
+    /// Whether the output ends with a line break; the output ends with one
+    /// when the input did if unset.
+    pub fn insert_final_newline(mut self, insert_final_newline: impl Into<Option<bool>>) -> Self {
+        self.insert_final_newline = insert_final_newline.into();
+        self
+    }
+
+    /// Reads indentation and line-ending settings that are left unset from
+    /// the `.editorconfig` files that apply to the formatted path.
+    pub fn editorconfig(mut self, editorconfig: bool) -> Self {
+        self.editorconfig = editorconfig;
+        self
+    }
+
+    /// What to do with repeated keys; by default every occurrence is kept.
+    pub fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    /// Fills the settings left unset from the `.editorconfig` files that
+    /// apply to `path`. Its indentation is only used when neither the indent
+    /// nor the indent style was set, so the two are never mixed.
+    pub(crate) fn with_editorconfig(&self, path: Option<&Path>) -> Result<Cow<'_, FormatOptions>> {
+        use ec4rs::property;
+
+        let Some(path) = path.filter(|_| self.editorconfig) else {
+            return Ok(Cow::Borrowed(self));
+        };
+        let properties = ec4rs::properties_of(path)?;
+        let mut options = self.clone();
+
+        if options.indent.is_none() && options.indent_style.is_none() {
+            options.indent_style = match properties.get::<property::IndentStyle>() {
+                Ok(property::IndentStyle::Tabs) => Some(IndentStyle::Tabs),
+                Ok(property::IndentStyle::Spaces) => Some(IndentStyle::Spaces),
+                Err(_) => None,
+            };
+            // A tab is one level whatever its width, so the size only
+            // applies to spaces.
+            if !matches!(options.indent_style, Some(IndentStyle::Tabs)) {
+                if let Ok(property::IndentSize::Value(size)) = properties.get() {
+                    options.indent = Some(size);
+                }
+            }
+        }
+        if options.end_of_line.is_none() {
+            options.end_of_line = match properties.get::<property::EndOfLine>() {
+                Ok(property::EndOfLine::Lf) => Some(EndOfLine::Lf),
+                Ok(property::EndOfLine::CrLf) => Some(EndOfLine::Crlf),
+                Ok(property::EndOfLine::Cr) => Some(EndOfLine::Cr),
+                Err(_) => None,
+            };
+        }
+        if options.insert_final_newline.is_none() {
+            if let Ok(property::FinalNewline::Value(value)) = properties.get() {
+                options.insert_final_newline = Some(value);
+            }
+        }
+
+        Ok(Cow::Owned(options))
+    }
+
+    pub(crate) fn sort_options(&self, path: Option<&Path>, print: &PrintOptions) -> SortOptions {
+        let options = match self.preset.as_ref().and_then(|preset| preset.resolve(path)) {
+            Some(preset) => preset.apply(&self.sort),
+            None => self.sort.clone(),
+        };
+        SortOptions {
+            print: print.clone(),
+            ..options
+        }
+    }
+
+    pub(crate) fn print_options(&self, input: &str, extension: Option<&Extension>) -> PrintOptions {
+        PrintOptions {
+            // Each JSON Lines record has to stay on its line.
+            style: match extension {
+                Some(extension) if extension.is_lines() => Style::Compact,
+                _ => self.style.clone(),
+            },
+            indent: self.indent_for(input),
+            escapes: self.escapes.clone(),
+            json5: extension == Some(&Extension::Json5),
+            // Plain JSON has no trailing commas, so only JSONC and JSON5 get them.
+            trailing_commas: match extension {
+                Some(Extension::Jsonc | Extension::Json5) => self.trailing_commas.clone(),
+                _ => TrailingCommas::Never,
+            },
+            print_width: self.print_width,
+        }
+    }
+
+    fn indent_for(&self, data: &str) -> String {
+        let detected_indent = detect_indent(data);
+
+        if let Some(indent) = self.indent {
+            return match self.indent_style {
+                Some(IndentStyle::Tabs) => "\t".repeat(indent),
+                _ => " ".repeat(indent),
+            };
+        }
+
+        if let Some(indent_style) = &self.indent_style {
+            return match indent_style {
+                IndentStyle::Tabs => "\t".to_string(),
+                IndentStyle::Spaces => {
+                    let width = detected_indent
+                        .as_ref()
+                        .map(|value| {
+                            if value.starts_with('\t') {
+                                2
+                            } else {
+                                value.len()
+                            }
+                        })
+                        .unwrap_or(2);
+                    " ".repeat(width)
+                }
+            };
+        }
+
+        detected_indent.unwrap_or_else(|| " ".to_string())
+    }
+}
+
+/// Sorts and pretty-prints a JSON, JSONC or JSON5 document.
+pub fn format_str(input: &str, options: &FormatOptions) -> Result<FormatOutcome> {
+    format_source(input, None, options)
+}
+
+/// Formats the file at `path` without writing it. The file name selects the
+/// preset when [`Preset::Auto`] is used and the `.editorconfig` settings when
+/// enabled, `.json5` files keep their JSON5 syntax, and each line of a
+/// `.jsonl` or `.ndjson` file is formatted like [`format_line`].
+pub fn format_file(path: &Path, options: &FormatOptions) -> Result<FormatOutcome> {
+    let input = fs::read_to_string(path)?;
+    format_str_as(&input, path, options)
+}
+
+/// Formats `input` as [`format_file`] would if it had been read from `path`,
+/// e.g. for an editor buffer that has not been saved yet.
+pub fn format_str_as(input: &str, path: &Path, options: &FormatOptions) -> Result<FormatOutcome> {
+    format_source(input, Some(path), options)
+}
+
+/// Formats one JSON Lines record the way each line of a `.jsonl` file is
+/// formatted: sorted and written compact, without a line break. Errors and
+/// duplicate keys report `number` as their line, e.g. while streaming.
+pub fn format_line(line: &str, number: usize, options: &FormatOptions) -> Result<FormatOutcome> {
+    let (output, duplicates) = format_record(line, number, None, options)?;
+    Ok(FormatOutcome {
+        changed: output != line,
+        output,
+        duplicates,
+    })
+}
+
+fn format_source(
+    input: &str,
+    path: Option<&Path>,
+    options: &FormatOptions,
+) -> Result<FormatOutcome> {
+    let options = options.with_editorconfig(path)?;
+    let extension = path.and_then(Extension::of);
+    let (mut output, duplicates) = match &extension {
+        Some(extension) if extension.is_lines() => format_lines(input, path, &options)?,
+        // The canonical form has no line breaks to adjust.
+        _ if options.canonical.is_some() => {
+            let (output, duplicates) = format_document(input, path, None, &options)?;
+            return Ok(FormatOutcome {
+                changed: output != input,
+                output,
+                duplicates,
+            });
+        }
+        _ => format_document(input, path, extension.as_ref(), &options)?,
+    };
+
+    if options
+        .insert_final_newline
+        .unwrap_or_else(|| input.ends_with(['\n', '\r']))
+    {
+        output.push('\n');
+    }
+    let end_of_line = match &options.end_of_line {
+        Some(end_of_line) => end_of_line.clone(),
+        None => detect_end_of_line(input),
+    };
+    let output = with_line_endings(&output, &end_of_line);
+    let changed = output != input;
+
+    Ok(FormatOutcome {
+        output,
+        changed,
+        duplicates,
+    })
+}
+
+/// Sorts and prints one document, without a final line break.
+fn format_document(
+    input: &str,
+    path: Option<&Path>,
+    extension: Option<&Extension>,
+    options: &FormatOptions,
+) -> Result<(String, Vec<DuplicateKey>)> {
+    let mut document = cst::parse(input)?;
+    if let Some(Canonical::Jcs) = options.canonical {
+        // RFC 8785 needs unique keys, so only one occurrence can be kept.
+        let duplicate_keys = match options.duplicate_keys {
+            DuplicateKeys::KeepAllAdjacent => DuplicateKeys::Error,
+            ref policy => policy.clone(),
+        };
+        document.resolve_duplicates(&duplicate_keys)?;
+        return Ok((canonical::jcs(&document.root)?, document.duplicates));
+    }
+    document.resolve_duplicates(&options.duplicate_keys)?;
+    let print = options.print_options(input, extension);
+
+    document.root = sort::sort(&document.root, &options.sort_options(path, &print));
+    Ok((cst::print(&document, &print), document.duplicates))
+}
+
+/// Formats every line of a JSON Lines file on its own; blank lines are kept
+/// empty.
+fn format_lines(
+    input: &str,
+    path: Option<&Path>,
+    options: &FormatOptions,
+) -> Result<(String, Vec<DuplicateKey>)> {
+    let mut output = String::with_capacity(input.len());
+    let mut duplicates = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (record, record_duplicates) = format_record(line, index + 1, path, options)?;
+        output.push_str(&record);
+        duplicates.extend(record_duplicates);
+    }
+    Ok((output, duplicates))
+}
+
+fn format_record(
+    line: &str,
+    number: usize,
+    path: Option<&Path>,
+    options: &FormatOptions,
+) -> Result<(String, Vec<DuplicateKey>)> {
+    match format_document(line, path, Some(&Extension::Jsonl), options) {
+        Ok((output, duplicates)) => Ok((
+            output,
+            duplicates
+                .into_iter()
+                .map(|duplicate| DuplicateKey {
+                    line: number,
+                    ..duplicate
+                })
+                .collect(),
+        )),
+        Err(Error::Parse(err)) => Err(Error::Parse(cst::Error {
+            line: number,
+            ..err
+        })),
+        Err(Error::DuplicateKeys(cst::DuplicateKeysError(duplicates))) => {
+            Err(Error::DuplicateKeys(cst::DuplicateKeysError(
+                duplicates
+                    .into_iter()
+                    .map(|duplicate| DuplicateKey {
+                        line: number,
+                        ..duplicate
+                    })
+                    .collect(),
+            )))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn detect_indent(json: &str) -> Option<String> {
+    json.lines()
+        .skip_while(|line| line.trim().is_empty())
+        .find_map(|line| {
+            let trimmed = line.trim_start();
+            if !trimmed.is_empty() && line.len() > trimmed.len() {
+                Some(line[..(line.len() - trimmed.len())].to_string())
+            } else {
+                None
+            }
+        })
+}
+
+pub(crate) fn detect_end_of_line(input: &str) -> EndOfLine {
+    match input.find(['\n', '\r']).map(|index| &input[index..]) {
+        Some(rest) if rest.starts_with("\r\n") => EndOfLine::Crlf,
+        Some(rest) if rest.starts_with('\r') => EndOfLine::Cr,
+        _ => EndOfLine::Lf,
+    }
+}
+
+/// Rewrites every line break in `text`, including those inside block
+/// comments, as `end_of_line`.
+fn with_line_endings(text: &str, end_of_line: &EndOfLine) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    match end_of_line {
+        EndOfLine::Lf => text,
+        end_of_line => text.replace('\n', end_of_line.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SortOrder;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_indent() {
+        let json = r#"
+{
+    "key": "value",
+    "nested": {
+        "inner": "value"
+    }
+}"#;
+        assert_eq!(detect_indent(json), Some("    ".to_string()));
+
+        let json_tabs = r#"
+{
+	"key": "value",
+	"nested": {
+		"inner": "value"
+	}
+}"#;
+        assert_eq!(detect_indent(json_tabs), Some("\t".to_string()));
+
+        let json_no_indent = r#"{"key": "value"}"#;
+        assert_eq!(detect_indent(json_no_indent), None);
+    }
+
+    #[test]
+    fn test_format_str() {
+        let options = FormatOptions::new().indent(2);
+        let outcome = format_str(r#"{"b": 1, "a": [true]}"#, &options).unwrap();
+        assert_eq!(
+            outcome.output,
+            "{\n  \"a\": [\n    true\n  ],\n  \"b\": 1\n}"
+        );
+        assert!(outcome.changed);
+
+        let outcome = format_str(&outcome.output, &options).unwrap();
+        assert!(!outcome.changed);
+
+        let options = FormatOptions::new().sort(SortOptions::new(SortOrder::Unsorted));
+        assert!(matches!(
+            format_str(r#"{"a": }"#, &options),
+            Err(Error::Parse(err)) if err.line == 1 && err.column == 7
+        ));
+    }
+
+    #[test]
+    fn test_format_str_string_escapes() {
+        let input = r#"{"caf\u00e9": "a\/b", "b": "é😀"}"#;
+        let format = |escapes| format_str(input, &FormatOptions::new().escapes(escapes)).unwrap();
+
+        assert_eq!(
+            format(StringEscapes::Preserve).output,
+            "{\n \"b\": \"é😀\",\n \"caf\\u00e9\": \"a\\/b\"\n}"
+        );
+        assert_eq!(
+            format(StringEscapes::Normalize).output,
+            "{\n \"b\": \"é😀\",\n \"café\": \"a/b\"\n}"
+        );
+        assert_eq!(
+            format(StringEscapes::Ascii).output,
+            "{\n \"b\": \"\\u00e9\\ud83d\\ude00\",\n \"caf\\u00e9\": \"a\\/b\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_format_str_trailing_commas() {
+        let input = "{\"b\": [1, 2,], \"a\": {}, \"c\": {\"d\": 1}}";
+        let format = |trailing_commas| {
+            let options = FormatOptions::new()
+                .indent(2)
+                .trailing_commas(trailing_commas);
+            format_str_as(input, Path::new("settings.jsonc"), &options)
+                .unwrap()
+                .output
+        };
+
+        assert_eq!(
+            format(TrailingCommas::Preserve),
+            "{\n  \"a\": {},\n  \"b\": [\n    1,\n    2,\n  ],\n  \"c\": {\n    \"d\": 1\n  }\n}"
+        );
+        assert_eq!(
+            format(TrailingCommas::Always),
+            "{\n  \"a\": {},\n  \"b\": [\n    1,\n    2,\n  ],\n  \"c\": {\n    \"d\": 1,\n  },\n}"
+        );
+        assert_eq!(
+            format(TrailingCommas::Never),
+            "{\n  \"a\": {},\n  \"b\": [\n    1,\n    2\n  ],\n  \"c\": {\n    \"d\": 1\n  }\n}"
+        );
+
+        // Plain JSON, or input of unknown kind, stays valid JSON.
+        let options = FormatOptions::new()
+            .indent(2)
+            .trailing_commas(TrailingCommas::Always);
+        let expected = format(TrailingCommas::Never);
+        assert_eq!(format_str(input, &options).unwrap().output, expected);
+        assert_eq!(
+            format_str_as(input, Path::new("settings.json"), &options)
+                .unwrap()
+                .output,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_dedupe_arrays_compares_exact_values() {
+        let options = FormatOptions::new()
+            .style(Style::Compact)
+            .sort(SortOptions {
+                dedupe_arrays: true,
+                ..SortOptions::default()
+            });
+        let format = |input: &str, path: &str| {
+            format_str_as(input, Path::new(path), &options)
+                .unwrap()
+                .output
+        };
+
+        assert_eq!(
+            format(
+                "[18446744073709551616, 18446744073709551617, 18446744073709551616]",
+                "a.json"
+            ),
+            "[18446744073709551616,18446744073709551617]"
+        );
+        assert_eq!(
+            format("{a: [null, Infinity, NaN, +Infinity, null]}", "a.json5"),
+            "{a:[null,Infinity,NaN]}"
+        );
+        assert_eq!(
+            format("[1, 1.0, 1e0, 10e-1, 0.1e1, 2, -0, 0.0]", "a.json"),
+            "[1,2,-0]"
+        );
+        assert_eq!(
+            format(
+                r#"[{"a": 1, "b": [2]}, {"b": [2.0], "a": 1}, "\u0061", "a"]"#,
+                "a.json"
+            ),
+            r#"[{"a":1,"b":[2]},"\u0061"]"#
+        );
+    }
+
+    #[test]
+    fn test_format_str_print_width() {
+        let input = r#"{"points": [[1, 2], [3, 4]], "enum": ["a", "b"], "nested": {"b": 1, "a": {}}, "long": ["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc"]}"#;
+        let path = Path::new("settings.jsonc");
+        let options = FormatOptions::new()
+            .indent(2)
+            .print_width(40)
+            .trailing_commas(TrailingCommas::Always);
+        assert_eq!(
+            format_str_as(input, path, &options).unwrap().output,
+            r#"{
+  "enum": ["a", "b"],
+  "long": [
+    "aaaaaaaaaa",
+    "bbbbbbbbbb",
+    "cccccccccc",
+  ],
+  "nested": { "a": {}, "b": 1 },
+  "points": [[1, 2], [3, 4]],
+}"#
+        );
+
+        let options = options.print_width(80);
+        assert_eq!(
+            format_str_as(r#"[1, /* two */ 2]"#, path, &options)
+                .unwrap()
+                .output,
+            "[\n  1, /* two */\n  2,\n]"
+        );
+        assert_eq!(
+            format_str_as(r#"{"a": [1, 2]}"#, path, &options)
+                .unwrap()
+                .output,
+            r#"{ "a": [1, 2] }"#
+        );
+    }
+
+    #[test]
+    fn test_format_json_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.ndjson");
+        fs::write(
+            &path,
+            "{\"b\": 1, \"a\": [2, 1]}\r\n\r\n{\"z\": {\"y\": null, \"x\": true}}\r\n",
+        )
+        .unwrap();
+
+        let options = FormatOptions::new().indent(4);
+        let outcome = format_file(&path, &options).unwrap();
+        assert_eq!(
+            outcome.output,
+            "{\"a\":[2,1],\"b\":1}\r\n\r\n{\"z\":{\"x\":true,\"y\":null}}\r\n"
+        );
+
+        fs::write(&path, "{}\n{\"a\": 1, \"a\": 2}\n").unwrap();
+        let outcome = format_file(&path, &options).unwrap();
+        assert_eq!(outcome.duplicates[0].line, 2);
+        fs::write(&path, "{}\n[1,\n").unwrap();
+        assert!(matches!(
+            format_file(&path, &options),
+            Err(Error::Parse(err)) if err.line == 2 && err.column == 4
+        ));
+
+        let outcome = format_line(r#"{"b": 1, "a": 2}"#, 7, &options).unwrap();
+        assert_eq!(outcome.output, r#"{"a":2,"b":1}"#);
+        assert!(outcome.changed);
+    }
+
+    #[test]
+    fn test_format_file_uses_editorconfig() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\nend_of_line = crlf\ninsert_final_newline = true\n\n[tabs/*.json]\nindent_style = tab\n",
+        )
+        .unwrap();
+        fs::create_dir(temp_dir.path().join("tabs")).unwrap();
+        let path = temp_dir.path().join("tabs/a.json");
+        fs::write(&path, "[1]").unwrap();
+        let spaces = temp_dir.path().join("b.json");
+        fs::write(&spaces, "[1]").unwrap();
+
+        let options = FormatOptions::new().editorconfig(true);
+        assert_eq!(
+            format_file(&path, &options).unwrap().output,
+            "[\r\n\t1\r\n]\r\n"
+        );
+        assert_eq!(
+            format_file(&spaces, &options).unwrap().output,
+            "[\r\n    1\r\n]\r\n"
+        );
+        assert_eq!(
+            format_file(
+                &spaces,
+                &options.clone().indent(1).insert_final_newline(false)
+            )
+            .unwrap()
+            .output,
+            "[\r\n 1\r\n]"
+        );
+        assert_eq!(
+            format_file(&spaces, &FormatOptions::new()).unwrap().output,
+            "[\n 1\n]"
+        );
+    }
+
+    #[test]
+    fn test_format_file_uses_file_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("package.json");
+        fs::write(&path, r#"{"version": "1.0.0", "name": "pkg"}"#).unwrap();
+
+        let options = FormatOptions::new().preset(Preset::Auto).indent(2);
+        let outcome = format_file(&path, &options).unwrap();
+        assert_eq!(
+            outcome.output,
+            "{\n  \"name\": \"pkg\",\n  \"version\": \"1.0.0\"\n}"
+        );
+
+        let json5 = temp_dir.path().join("config.json5");
+        fs::write(&json5, "{b: 0x1F, 'a': +Infinity, c: .5,}\n").unwrap();
+        assert_eq!(
+            format_file(&json5, &options).unwrap().output,
+            "{\n  'a': +Infinity,\n  b: 0x1F,\n  c: .5\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_str_line_endings() {
+        let options = FormatOptions::new().indent(2);
+        let format = |input, options: &FormatOptions| format_str(input, options).unwrap().output;
+
+        assert_eq!(
+            format("{\r\n\"b\": 1 /* x\n y */, \"a\": 2}\r\n", &options),
+            "{\r\n  \"a\": 2,\r\n  \"b\": 1 /* x\r\n y */\r\n}\r\n"
+        );
+        assert_eq!(format("[1]\n", &options), "[\n  1\n]\n");
+        assert_eq!(format("[1]", &options), "[\n  1\n]");
+
+        let options = options
+            .end_of_line(EndOfLine::Crlf)
+            .insert_final_newline(false);
+        assert_eq!(format("[1]\n", &options), "[\r\n  1\r\n]");
+    }
+}
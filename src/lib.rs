@@ -1,9 +1,24 @@
+pub mod canonical;
+pub mod cst;
+pub mod diff;
+pub mod files;
+pub mod format;
+pub mod preset;
+pub mod report;
+pub mod selector;
 pub mod sort;
+pub mod stream;
 
 use clap::ValueEnum;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, ValueEnum)]
+pub use format::{
+    format_file, format_line, format_str, format_str_as, FormatOptions, FormatOutcome,
+};
+
+#[derive(Debug, Clone, Default, ValueEnum)]
 pub enum SortOrder {
+    #[default]
     #[clap(name = "asc", alias = "alphabetical-asc", alias = "a")]
     AlphabeticalAsc,
     #[clap(name = "desc", alias = "alphabetical-desc", alias = "d")]
@@ -16,4 +31,144 @@ pub enum SortOrder {
     KeyLengthDesc,
     #[clap(name = "line-length", alias = "ll")]
     LineLength,
+    #[clap(name = "natural-asc", alias = "nat-asc", alias = "na")]
+    NaturalAsc,
+    #[clap(name = "natural-desc", alias = "nat-desc", alias = "nd")]
+    NaturalDesc,
+    #[clap(name = "value-asc", alias = "va")]
+    ValueAsc,
+    #[clap(name = "value-desc", alias = "vd")]
+    ValueDesc,
+    #[clap(name = "value-type", alias = "vt")]
+    ValueType,
+    #[clap(name = "none", alias = "preserve")]
+    Unsorted,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ArrayOrder {
+    #[clap(name = "asc", alias = "a")]
+    Asc,
+    #[clap(name = "desc", alias = "d")]
+    Desc,
+}
+
+#[derive(Debug, Clone, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    #[clap()]
+    Tabs,
+    #[clap()]
+    Spaces,
+}
+
+/// What to do with keys that appear more than once in the same object.
+#[derive(Debug, Clone, Default, PartialEq, ValueEnum)]
+pub enum DuplicateKeys {
+    /// Fail with the position of the first duplicate
+    #[clap(name = "error")]
+    Error,
+    /// Keep the first occurrence of each key
+    #[clap(name = "keep-first")]
+    KeepFirst,
+    /// Keep the value of the last occurrence, like most JSON parsers do
+    #[clap(name = "keep-last")]
+    KeepLast,
+    /// Keep every occurrence, moved next to the first one
+    #[default]
+    #[clap(name = "keep-all-adjacent")]
+    KeepAllAdjacent,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, ValueEnum)]
+pub enum StringEscapes {
+    /// Keep every string exactly as it was written
+    #[default]
+    #[clap(name = "preserve")]
+    Preserve,
+    /// Re-encode strings, writing non-ASCII characters unescaped
+    #[clap(name = "normalize")]
+    Normalize,
+    /// Keep strings as written, but escape every non-ASCII character
+    #[clap(name = "ascii")]
+    Ascii,
+}
+
+/// The overall layout of the output.
+#[derive(Debug, Clone, Default, PartialEq, ValueEnum)]
+pub enum Style {
+    /// One member or item per line, indented
+    #[default]
+    #[clap(name = "pretty")]
+    Pretty,
+    /// No insignificant whitespace and no comments
+    #[clap(name = "compact")]
+    Compact,
+}
+
+/// The line break written between lines.
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum EndOfLine {
+    #[clap(name = "lf")]
+    Lf,
+    #[clap(name = "crlf")]
+    Crlf,
+    #[clap(name = "cr")]
+    Cr,
+}
+
+impl EndOfLine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndOfLine::Lf => "\n",
+            EndOfLine::Crlf => "\r\n",
+            EndOfLine::Cr => "\r",
+        }
+    }
+}
+
+/// Whether the last member of an object or array is followed by a comma.
+#[derive(Debug, Clone, Default, PartialEq, ValueEnum)]
+pub enum TrailingCommas {
+    /// Keep a trailing comma only where the input had one
+    #[clap(name = "preserve")]
+    Preserve,
+    /// Add a trailing comma after every last member and item
+    #[clap(name = "always")]
+    Always,
+    /// Remove every trailing comma, as plain JSON requires
+    #[default]
+    #[clap(name = "never")]
+    Never,
+}
+
+#[derive(Debug, Clone, Default, ValueEnum)]
+pub enum Collation {
+    /// Compare keys by their UTF-8 bytes
+    #[default]
+    #[clap(name = "binary")]
+    Binary,
+    /// Ignore case when comparing keys
+    #[clap(name = "case-insensitive", alias = "ci")]
+    CaseInsensitive,
+    /// Ignore case, but put lowercase before uppercase on ties
+    #[clap(name = "lower-first")]
+    LowerFirst,
+    /// Ignore case, but put uppercase before lowercase on ties
+    #[clap(name = "upper-first")]
+    UpperFirst,
+    /// Unicode collation for the configured locale
+    #[clap(name = "unicode")]
+    Unicode,
+    /// Compare keys by their UTF-16 code units, as JavaScript does
+    #[clap(name = "utf16")]
+    Utf16,
+}
+
+/// A canonical form that fixes every byte of the output.
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum Canonical {
+    /// RFC 8785 JSON Canonicalization Scheme
+    #[clap(name = "jcs")]
+    Jcs,
 }
@@ -1,47 +1,40 @@
 use clap::{CommandFactory, Parser, ValueEnum};
 use colored::*;
+use icu_locid::Locale;
 use log::{error, info, LevelFilter};
 use rayon::prelude::*;
 use serde::Deserialize;
-use serde::Serialize;
-use serde_json::Value;
-use std::collections::{HashMap, VecDeque};
 use std::fs;
-use std::io::{self, Read};
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
-use tidy_json::sort;
-use tidy_json::SortOrder;
-
-mod files;
+use tidy_json::cst::DuplicateKey;
+use tidy_json::format::{self, FormatOptions};
+use tidy_json::preset::Preset;
+use tidy_json::report::{ErrorKind, FileReport, FileStatus, ReportError, Reporter};
+use tidy_json::sort::{self, SortOptions, SortRule};
+use tidy_json::{diff, files, stream};
+use tidy_json::{
+    ArrayOrder, Canonical, Collation, DuplicateKeys, EndOfLine, IndentStyle, SortOrder,
+    StringEscapes, Style, TrailingCommas,
+};
 
 #[derive(Error, Debug)]
 enum CustomError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Format(#[from] format::Error),
     #[error("Failed to read config: {0}")]
     Toml(#[from] toml::de::Error),
-    #[error("{0}")]
-    Anyhow(#[from] anyhow::Error),
-    #[error("{0} file(s) need formatting")]
+    #[error("{0} file(s) need formatting or could not be processed")]
     CheckFailed(usize),
     #[error("{0}")]
     Custom(String),
 }
 
-#[derive(Debug, Clone, ValueEnum, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum IndentStyle {
-    #[clap()]
-    Tabs,
-    #[clap()]
-    Spaces,
-}
-
 #[derive(Debug, Clone, ValueEnum, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
@@ -88,6 +81,14 @@ struct Args {
     #[arg(long, default_value = "false")]
     check: bool,
 
+    /// Print a unified diff of the changes each file needs
+    #[arg(long, default_value = "false")]
+    diff: bool,
+
+    /// Print the results in a machine-readable format
+    #[arg(long, value_enum)]
+    reporter: Option<Reporter>,
+
     /// Specify how deep the sorting should go
     #[arg(short, long)]
     depth: Option<u32>,
@@ -96,6 +97,54 @@ struct Args {
     #[arg(short = 'o', long, value_enum)]
     order: Option<SortOrder>,
 
+    /// Seed for the random order, to reproduce a previous shuffle
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Compare objects by this key (or JSON Pointer) in the value orders
+    #[arg(long)]
+    order_by: Option<String>,
+
+    /// Keys to place first, in the given order
+    #[arg(long, value_delimiter = ',')]
+    first: Option<Vec<String>>,
+
+    /// Keys to place last, in the given order
+    #[arg(long, value_delimiter = ',')]
+    last: Option<Vec<String>>,
+
+    /// Sort array elements by value
+    #[arg(long, value_enum)]
+    sort_arrays: Option<ArrayOrder>,
+
+    /// Sort arrays of objects by the value of this key
+    #[arg(long)]
+    sort_arrays_by: Option<String>,
+
+    /// Remove duplicate array elements
+    #[arg(long, default_value = "false")]
+    dedupe_arrays: bool,
+
+    /// Apply the key order of a well-known file schema
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+
+    /// Specify how keys are compared
+    #[arg(long, value_enum)]
+    collation: Option<Collation>,
+
+    /// Locale for unicode collation (e.g., de, sv)
+    #[arg(long)]
+    locale: Option<String>,
+
+    /// Write a canonical form, ignoring the sort and layout options
+    #[arg(long, value_enum)]
+    canonical: Option<Canonical>,
+
+    /// Write minified JSON with no whitespace or comments
+    #[arg(long, default_value = "false")]
+    minify: bool,
+
     /// Specify the desired indent
     #[arg(short, long)]
     indent: Option<usize>,
@@ -104,6 +153,38 @@ struct Args {
     #[arg(long)]
     indent_style: Option<IndentStyle>,
 
+    /// Keep objects and arrays on one line when they fit within this width
+    #[arg(long)]
+    print_width: Option<usize>,
+
+    /// What to do with repeated keys [default: error with --check, otherwise keep-all-adjacent]
+    #[arg(long, value_enum)]
+    duplicate_keys: Option<DuplicateKeys>,
+
+    /// How string escapes are written
+    #[arg(long, value_enum)]
+    escapes: Option<StringEscapes>,
+
+    /// Escape all non-ASCII characters (same as --escapes=ascii)
+    #[arg(long, default_value = "false")]
+    ascii_only: bool,
+
+    /// Whether objects and arrays end with a comma (for JSONC and JSON5)
+    #[arg(long, value_enum)]
+    trailing_commas: Option<TrailingCommas>,
+
+    /// Line break to write [default: the input's]
+    #[arg(long, value_enum)]
+    end_of_line: Option<EndOfLine>,
+
+    /// End the output with a line break [default: if the input did]
+    #[arg(long)]
+    insert_final_newline: Option<bool>,
+
+    /// Ignore .editorconfig files
+    #[arg(long, default_value = "false")]
+    no_editorconfig: bool,
+
     /// Specify the log level
     #[arg(value_enum, long)]
     log_level: Option<LogLevel>,
@@ -112,10 +193,18 @@ struct Args {
     #[arg(long, default_value = "false")]
     stdin: bool,
 
+    /// Read stdin as JSON Lines, writing each line as soon as it is formatted
+    #[arg(long, default_value = "false")]
+    jsonl: bool,
+
     /// Print sorted output to stdout
     #[arg(long, default_value = "false")]
     stdout: bool,
 
+    /// Format files without reading them into memory whole, reporting progress
+    #[arg(long, default_value = "false")]
+    stream: bool,
+
     /// Path to a TOML config file
     #[arg(long)]
     config: Option<PathBuf>,
@@ -128,13 +217,50 @@ struct FileConfig {
     write: Option<bool>,
     backup: Option<bool>,
     check: Option<bool>,
+    diff: Option<bool>,
+    reporter: Option<String>,
     order: Option<String>,
+    order_by: Option<String>,
+    seed: Option<u64>,
+    first: Option<Vec<String>>,
+    last: Option<Vec<String>>,
+    sort_arrays: Option<String>,
+    sort_arrays_by: Option<String>,
+    dedupe_arrays: Option<bool>,
+    preset: Option<String>,
+    collation: Option<String>,
+    locale: Option<String>,
     depth: Option<u32>,
+    canonical: Option<String>,
+    style: Option<String>,
     indent: Option<usize>,
     indent_style: Option<String>,
+    print_width: Option<usize>,
+    escapes: Option<String>,
+    ascii_only: Option<bool>,
+    trailing_commas: Option<String>,
+    end_of_line: Option<String>,
+    insert_final_newline: Option<bool>,
+    editorconfig: Option<bool>,
+    duplicate_keys: Option<String>,
     log_level: Option<String>,
     stdin: Option<bool>,
+    jsonl: Option<bool>,
     stdout: Option<bool>,
+    stream: Option<bool>,
+    rules: Option<Vec<RuleConfig>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleConfig {
+    path: String,
+    order: Option<String>,
+    order_by: Option<String>,
+    first: Option<Vec<String>>,
+    last: Option<Vec<String>>,
+    sort_arrays: Option<String>,
+    sort_arrays_by: Option<String>,
+    dedupe_arrays: Option<bool>,
 }
 
 #[derive(Debug)]
@@ -144,19 +270,22 @@ struct Configuration {
     write: bool,
     backup: bool,
     check: bool,
-    order: SortOrder,
-    depth: Option<u32>,
-    indent: Option<usize>,
-    indent_style: Option<IndentStyle>,
+    diff: bool,
+    reporter: Option<Reporter>,
+    format: FormatOptions,
     log_level: LogLevel,
     stdin: bool,
+    jsonl: bool,
     stdout: bool,
+    stream: bool,
 }
 
 #[derive(Debug)]
 struct ProcessOutcome {
     changed: bool,
     output: Option<String>,
+    diff: Option<String>,
+    duplicates: Vec<DuplicateKey>,
 }
 
 fn print_error(err: &CustomError) {
@@ -214,28 +343,109 @@ fn resolve_configuration(args: Args) -> Result<Configuration> {
         Some(order) => order,
         None => parse_sort_order(file_cfg.order.as_deref())?.unwrap_or(SortOrder::AlphabeticalAsc),
     };
+    let locale = parse_locale(args.locale.as_deref().or(file_cfg.locale.as_deref()))?;
+    let collation = match args.collation {
+        Some(collation) => collation,
+        None => parse_collation(file_cfg.collation.as_deref())?.unwrap_or(if locale.is_some() {
+            Collation::Unicode
+        } else {
+            Collation::Binary
+        }),
+    };
+    let preset = match args.preset {
+        Some(preset) => Some(preset),
+        None => parse_preset(file_cfg.preset.as_deref())?,
+    };
+    let canonical = match args.canonical {
+        Some(canonical) => Some(canonical),
+        None => parse_canonical(file_cfg.canonical.as_deref())?,
+    };
+    let style = if args.minify {
+        Style::Compact
+    } else {
+        parse_style(file_cfg.style.as_deref())?.unwrap_or_default()
+    };
     let indent_style = match args.indent_style {
         Some(indent_style) => Some(indent_style),
         None => parse_indent_style(file_cfg.indent_style.as_deref())?,
     };
+    let escapes = if args.ascii_only || file_cfg.ascii_only.unwrap_or(false) {
+        StringEscapes::Ascii
+    } else {
+        match args.escapes {
+            Some(escapes) => escapes,
+            None => parse_escapes(file_cfg.escapes.as_deref())?.unwrap_or_default(),
+        }
+    };
+    let trailing_commas = match args.trailing_commas {
+        Some(trailing_commas) => trailing_commas,
+        None => parse_trailing_commas(file_cfg.trailing_commas.as_deref())?.unwrap_or_default(),
+    };
+    let end_of_line = match args.end_of_line {
+        Some(end_of_line) => Some(end_of_line),
+        None => parse_end_of_line(file_cfg.end_of_line.as_deref())?,
+    };
+    let check = args.check || file_cfg.check.unwrap_or(false);
+    let duplicate_keys = match args.duplicate_keys {
+        Some(duplicate_keys) => duplicate_keys,
+        None => parse_duplicate_keys(file_cfg.duplicate_keys.as_deref())?.unwrap_or(if check {
+            DuplicateKeys::Error
+        } else {
+            DuplicateKeys::KeepAllAdjacent
+        }),
+    };
+    let reporter = match args.reporter {
+        Some(reporter) => Some(reporter),
+        None => parse_reporter(file_cfg.reporter.as_deref())?,
+    };
     let log_level = match args.log_level {
         Some(log_level) => log_level,
         None => parse_log_level(file_cfg.log_level.as_deref())?.unwrap_or(LogLevel::Default),
     };
 
+    let mut sort = SortOptions::new(order);
+    sort.depth = args.depth.or(file_cfg.depth);
+    sort.order_by = args.order_by.or(file_cfg.order_by);
+    sort.seed = args.seed.or(file_cfg.seed);
+    sort.first = args.first.or(file_cfg.first).unwrap_or_default();
+    sort.last = args.last.or(file_cfg.last).unwrap_or_default();
+    sort.collation = collation;
+    sort.locale = locale;
+    sort.sort_arrays = match args.sort_arrays {
+        Some(order) => Some(order),
+        None => parse_array_order(file_cfg.sort_arrays.as_deref())?,
+    };
+    sort.sort_arrays_by = args.sort_arrays_by.or(file_cfg.sort_arrays_by);
+    sort.dedupe_arrays = args.dedupe_arrays || file_cfg.dedupe_arrays.unwrap_or(false);
+    sort.rules = parse_rules(file_cfg.rules.unwrap_or_default())?;
+
     let cfg = Configuration {
         backup: args.backup || file_cfg.backup.unwrap_or(false),
-        check: args.check || file_cfg.check.unwrap_or(false),
-        depth: args.depth.or(file_cfg.depth),
+        check,
+        diff: args.diff || file_cfg.diff.unwrap_or(false),
+        reporter,
         exclude,
         include,
-        indent: args.indent.or(file_cfg.indent),
-        order,
+        format: FormatOptions::new()
+            .sort(sort)
+            .preset(preset)
+            .canonical(canonical)
+            .style(style)
+            .indent(args.indent.or(file_cfg.indent))
+            .indent_style(indent_style)
+            .print_width(args.print_width.or(file_cfg.print_width))
+            .escapes(escapes)
+            .trailing_commas(trailing_commas)
+            .end_of_line(end_of_line)
+            .insert_final_newline(args.insert_final_newline.or(file_cfg.insert_final_newline))
+            .editorconfig(!args.no_editorconfig && file_cfg.editorconfig.unwrap_or(true))
+            .duplicate_keys(duplicate_keys),
         write: args.write || file_cfg.write.unwrap_or(false),
-        indent_style,
         log_level,
         stdin: args.stdin || file_cfg.stdin.unwrap_or(false),
+        jsonl: args.jsonl || file_cfg.jsonl.unwrap_or(false),
         stdout: args.stdout || file_cfg.stdout.unwrap_or(false),
+        stream: args.stream || file_cfg.stream.unwrap_or(false),
     };
 
     validate_configuration(&cfg)?;
@@ -274,6 +484,94 @@ fn parse_sort_order(value: Option<&str>) -> Result<Option<SortOrder>> {
         .transpose()
 }
 
+fn parse_rules(rules: Vec<RuleConfig>) -> Result<Vec<SortRule>> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            Ok(SortRule {
+                path: rule
+                    .path
+                    .parse()
+                    .map_err(|err| CustomError::Custom(format!("Invalid rule in config: {err}")))?,
+                order: parse_sort_order(rule.order.as_deref())?,
+                order_by: rule.order_by,
+                first: rule.first,
+                last: rule.last,
+                sort_arrays: parse_array_order(rule.sort_arrays.as_deref())?,
+                sort_arrays_by: rule.sort_arrays_by,
+                dedupe_arrays: rule.dedupe_arrays,
+            })
+        })
+        .collect()
+}
+
+fn parse_array_order(value: Option<&str>) -> Result<Option<ArrayOrder>> {
+    value
+        .map(|v| {
+            ArrayOrder::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid array order in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_preset(value: Option<&str>) -> Result<Option<Preset>> {
+    value
+        .map(|v| {
+            Preset::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid preset in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_reporter(value: Option<&str>) -> Result<Option<Reporter>> {
+    value
+        .map(|v| {
+            Reporter::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid reporter in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_collation(value: Option<&str>) -> Result<Option<Collation>> {
+    value
+        .map(|v| {
+            Collation::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid collation in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_locale(value: Option<&str>) -> Result<Option<Locale>> {
+    value
+        .map(|v| {
+            let locale = v
+                .parse::<Locale>()
+                .map_err(|_| CustomError::Custom(format!("Invalid locale: {v}")))?;
+            sort::check_locale(&locale)
+                .map_err(|e| CustomError::Custom(format!("Unsupported locale {v}: {e}")))?;
+            Ok(locale)
+        })
+        .transpose()
+}
+
+fn parse_canonical(value: Option<&str>) -> Result<Option<Canonical>> {
+    value
+        .map(|v| {
+            Canonical::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid canonical form in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_style(value: Option<&str>) -> Result<Option<Style>> {
+    value
+        .map(|v| {
+            Style::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid style in config: {v}")))
+        })
+        .transpose()
+}
+
 fn parse_indent_style(value: Option<&str>) -> Result<Option<IndentStyle>> {
     value
         .map(|v| {
@@ -283,6 +581,43 @@ fn parse_indent_style(value: Option<&str>) -> Result<Option<IndentStyle>> {
         .transpose()
 }
 
+fn parse_duplicate_keys(value: Option<&str>) -> Result<Option<DuplicateKeys>> {
+    value
+        .map(|v| {
+            DuplicateKeys::from_str(v, true).map_err(|_| {
+                CustomError::Custom(format!("Invalid duplicate key policy in config: {v}"))
+            })
+        })
+        .transpose()
+}
+
+fn parse_escapes(value: Option<&str>) -> Result<Option<StringEscapes>> {
+    value
+        .map(|v| {
+            StringEscapes::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid escapes in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_trailing_commas(value: Option<&str>) -> Result<Option<TrailingCommas>> {
+    value
+        .map(|v| {
+            TrailingCommas::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid trailing commas in config: {v}")))
+        })
+        .transpose()
+}
+
+fn parse_end_of_line(value: Option<&str>) -> Result<Option<EndOfLine>> {
+    value
+        .map(|v| {
+            EndOfLine::from_str(v, true)
+                .map_err(|_| CustomError::Custom(format!("Invalid end of line in config: {v}")))
+        })
+        .transpose()
+}
+
 fn parse_log_level(value: Option<&str>) -> Result<Option<LogLevel>> {
     value
         .map(|v| {
@@ -309,12 +644,30 @@ fn validate_configuration(cfg: &Configuration) -> Result<()> {
                 "--backup is not supported with --stdin".to_string(),
             ));
         }
+        if cfg.jsonl && cfg.diff {
+            return Err(CustomError::Custom(
+                "--diff is not supported with --jsonl".to_string(),
+            ));
+        }
+    } else if cfg.jsonl {
+        return Err(CustomError::Custom("--jsonl requires --stdin".to_string()));
     } else if cfg.include.is_empty() {
         return Err(CustomError::Custom(
             "No include file patterns provided".to_string(),
         ));
     }
 
+    if cfg.stream && (cfg.stdin || cfg.stdout || cfg.diff) {
+        return Err(CustomError::Custom(
+            "--stream cannot be combined with --stdin, --stdout or --diff".to_string(),
+        ));
+    }
+
+    if cfg.reporter.is_some() && (cfg.stdin || cfg.stdout || cfg.diff) {
+        return Err(CustomError::Custom(
+            "--reporter cannot be combined with --stdin, --stdout or --diff".to_string(),
+        ));
+    }
     Ok(())
 }
 
@@ -326,8 +679,14 @@ fn run(cfg: &Configuration) -> Result<()> {
     let start_time = Instant::now();
     let files = files::list_files(
         &cfg.include,
-        &cfg.exclude,
-        vec![files::Extension::Json, files::Extension::Jsonc],
+        cfg.exclude.as_deref().unwrap_or_default(),
+        vec![
+            files::Extension::Json,
+            files::Extension::Jsonc,
+            files::Extension::Json5,
+            files::Extension::Jsonl,
+            files::Extension::Ndjson,
+        ],
     )?;
 
     if files.is_empty() {
@@ -340,7 +699,7 @@ fn run(cfg: &Configuration) -> Result<()> {
         .par_iter()
         .map(|path| {
             let file_start_time = Instant::now();
-            let result = process_file(path, &cfg);
+            let result = process_file(path, cfg);
             let duration = file_start_time.elapsed();
             (path, result, duration)
         })
@@ -357,13 +716,51 @@ fn run(cfg: &Configuration) -> Result<()> {
         .count();
 
     let total_files = files.len();
+    let total_duration = start_time.elapsed();
+
+    match &cfg.reporter {
+        Some(reporter) => {
+            let reports: Vec<_> = results
+                .iter()
+                .map(|(path, result, duration)| file_report(cfg, path, result, *duration))
+                .collect();
+            println!("{}", reporter.render(&reports, total_duration));
+        }
+        None => print_results(cfg, results, total_files),
+    }
+
+    info!(
+        "{}",
+        format!(
+            "Processed {} file(s) in {:.2?}",
+            successful_files, total_duration
+        )
+        .green()
+    );
+
+    // Files that could not be formatted, e.g. because of a duplicate key,
+    // fail the check as well.
+    let failed_files = total_files - successful_files;
+    if cfg.check && changed_files + failed_files > 0 {
+        return Err(CustomError::CheckFailed(changed_files + failed_files));
+    }
 
+    Ok(())
+}
+
+fn print_results(
+    cfg: &Configuration,
+    results: Vec<(&PathBuf, Result<ProcessOutcome>, Duration)>,
+    total_files: usize,
+) {
     for (path, result, duration) in results {
         match result {
             Ok(outcome) => {
+                print_duplicates(cfg, &path.display().to_string(), &outcome.duplicates);
+
                 if cfg.stdout {
                     if let Some(output) = outcome.output {
-                        print_output(&path, &output, total_files);
+                        print_output(path, &output, total_files);
                     }
                 }
 
@@ -371,7 +768,11 @@ fn run(cfg: &Configuration) -> Result<()> {
                     println!("{} needs formatting", path.display());
                 }
 
-                if !cfg.write && !cfg.check && !cfg.stdout && !is_quiet(cfg) {
+                if let Some(diff) = outcome.diff {
+                    print_diff(&diff);
+                }
+
+                if !cfg.write && !cfg.check && !cfg.stdout && !cfg.diff && !is_quiet(cfg) {
                     let status = if outcome.changed {
                         "Needs formatting"
                     } else {
@@ -396,26 +797,40 @@ fn run(cfg: &Configuration) -> Result<()> {
             ),
         }
     }
+}
 
-    let total_duration = start_time.elapsed();
-
-    info!(
-        "{}",
-        format!(
-            "Processed {} file(s) in {:.2?}",
-            successful_files, total_duration
-        )
-        .green()
-    );
-
-    if cfg.check && changed_files > 0 {
-        return Err(CustomError::CheckFailed(changed_files));
+fn file_report(
+    cfg: &Configuration,
+    path: &Path,
+    result: &Result<ProcessOutcome>,
+    duration: Duration,
+) -> FileReport {
+    let status = match result {
+        Ok(outcome) if !outcome.changed => FileStatus::Formatted,
+        Ok(_) if cfg.write => FileStatus::Reformatted,
+        Ok(_) => FileStatus::NeedsFormatting,
+        Err(CustomError::Format(err)) => FileStatus::Error(ReportError::from_error(err)),
+        Err(err @ CustomError::Io(_)) => {
+            FileStatus::Error(vec![ReportError::new(ErrorKind::Io, err.to_string())])
+        }
+        Err(err) => FileStatus::Error(vec![ReportError::new(ErrorKind::Other, err.to_string())]),
+    };
+    FileReport {
+        path: path.to_path_buf(),
+        status,
+        duration,
+        duplicates: match result {
+            Ok(outcome) => outcome.duplicates.clone(),
+            Err(_) => Vec::new(),
+        },
     }
-
-    Ok(())
 }
 
 fn run_stdin(cfg: &Configuration) -> Result<()> {
+    if cfg.jsonl {
+        return run_stdin_lines(cfg);
+    }
+
     let mut data = String::new();
     io::stdin().read_to_string(&mut data)?;
     if data.trim().is_empty() {
@@ -424,14 +839,43 @@ fn run_stdin(cfg: &Configuration) -> Result<()> {
         ));
     }
 
-    let json: Value = parse_json_value(&data)?;
-    let sorted_json = sort::sort(&json, &cfg.order, 0, cfg.depth);
-    let indent = get_indent(cfg, &data);
-    let formatted_json = format_json(&sorted_json, &indent)?;
-    let changed = formatted_json != data;
+    let outcome = format::format_str(&data, &cfg.format)?;
+    print_duplicates(cfg, "<stdin>", &outcome.duplicates);
+
+    if cfg.diff {
+        print_diff(&diff::unified_diff("<stdin>", &data, &outcome.output));
+    }
 
-    if cfg.stdout || !cfg.check {
-        println!("{formatted_json}");
+    if cfg.stdout || !(cfg.check || cfg.diff) {
+        print_document(&outcome.output);
+    }
+
+    if cfg.check && outcome.changed {
+        return Err(CustomError::CheckFailed(1));
+    }
+
+    Ok(())
+}
+
+/// Formats JSON Lines from stdin one line at a time, so that output starts
+/// before the input ends.
+fn run_stdin_lines(cfg: &Configuration) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut changed = false;
+
+    for (index, line) in io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let output = if line.trim().is_empty() {
+            String::new()
+        } else {
+            let outcome = format::format_line(&line, index + 1, &cfg.format)?;
+            print_duplicates(cfg, "<stdin>", &outcome.duplicates);
+            changed |= outcome.changed;
+            outcome.output
+        };
+        if cfg.stdout || !cfg.check {
+            writeln!(stdout, "{output}")?;
+        }
     }
 
     if cfg.check && changed {
@@ -442,13 +886,21 @@ fn run_stdin(cfg: &Configuration) -> Result<()> {
 }
 
 fn process_file(path: &PathBuf, cfg: &Configuration) -> Result<ProcessOutcome> {
-    let data = fs::read_to_string(path)?;
-    let json: Value = parse_json_value(&data)?;
+    if cfg.stream {
+        return stream_file(path, cfg);
+    }
 
-    let sorted_json = sort::sort(&json, &cfg.order, 0, cfg.depth);
-    let indent = get_indent(cfg, &data);
-    let formatted_json = format_json(&sorted_json, &indent)?;
-    let changed = formatted_json != data;
+    let data = fs::read_to_string(path)?;
+    let format::FormatOutcome {
+        output,
+        changed,
+        duplicates,
+    } = format::format_str_as(&data, path, &cfg.format)?;
+    let diff = if cfg.diff && changed {
+        Some(diff::unified_diff(&path.to_string_lossy(), &data, &output))
+    } else {
+        None
+    };
 
     if cfg.write {
         if cfg.backup && changed {
@@ -458,226 +910,128 @@ fn process_file(path: &PathBuf, cfg: &Configuration) -> Result<ProcessOutcome> {
         }
 
         if changed {
-            let formatted_json = if is_jsonc(path) {
-                restore_jsonc_leading_comments(&data, &formatted_json)
-            } else {
-                formatted_json.clone()
-            };
-            fs::write(path, &formatted_json)?;
+            fs::write(path, &output)?;
             info!("Sorted JSON written back to {:?}", path);
         }
     }
 
     let output = if cfg.stdout && !cfg.write {
-        Some(formatted_json)
+        Some(output)
     } else {
         None
     };
 
-    Ok(ProcessOutcome { changed, output })
+    Ok(ProcessOutcome {
+        changed,
+        output,
+        diff,
+        duplicates,
+    })
 }
 
-fn detect_indent(json: &str) -> Option<String> {
-    json.lines()
-        .skip_while(|line| line.trim().is_empty())
-        .find_map(|line| {
-            let trimmed = line.trim_start();
-            if !trimmed.is_empty() && line.len() > trimmed.len() {
-                Some(line[..(line.len() - trimmed.len())].to_string())
-            } else {
-                None
-            }
-        })
-}
-
-fn get_indent(cfg: &Configuration, data: &str) -> String {
-    let detected_indent = detect_indent(data);
-
-    if let Some(indent) = cfg.indent {
-        return match cfg.indent_style {
-            Some(IndentStyle::Tabs) => "\t".repeat(indent),
-            _ => " ".repeat(indent),
-        };
-    }
+/// Formats a file without reading it whole, writing through a temporary file
+/// next to it that replaces the original once it is complete.
+fn stream_file(path: &Path, cfg: &Configuration) -> Result<ProcessOutcome> {
+    let mut reported = 0;
+    let progress = |read: u64, total: u64| {
+        let percent = (read * 100).checked_div(total).unwrap_or(100) / 10 * 10;
+        if percent > reported && !is_quiet(cfg) {
+            reported = percent;
+            eprintln!(
+                "{}: {percent}% ({} of {} MiB)",
+                path.display(),
+                read >> 20,
+                total >> 20
+            );
+        }
+    };
 
-    if let Some(indent_style) = &cfg.indent_style {
-        return match indent_style {
-            IndentStyle::Tabs => "\t".to_string(),
-            IndentStyle::Spaces => {
-                let width = detected_indent
-                    .as_ref()
-                    .map(|value| {
-                        if value.starts_with('\t') {
-                            2
-                        } else {
-                            value.len()
-                        }
-                    })
-                    .unwrap_or(2);
-                " ".repeat(width)
-            }
-        };
+    if !cfg.write {
+        let outcome = stream::format_stream(path, io::sink(), &cfg.format, progress)?;
+        return Ok(ProcessOutcome {
+            changed: outcome.changed,
+            output: None,
+            diff: None,
+            duplicates: outcome.duplicates,
+        });
     }
 
-    detected_indent.unwrap_or_else(|| " ".to_string())
-}
-
-fn format_json(value: &serde_json::Value, indent: &str) -> Result<String> {
-    let mut buf = Vec::new();
-    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
-    let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
-    value.serialize(&mut ser).map_err(CustomError::Json)?;
-    String::from_utf8(buf).map_err(|err| CustomError::Custom(err.to_string()))
-}
+    let temp_path = path.with_extension("tidy-json.tmp");
+    let result = fs::File::create(&temp_path)
+        .map_err(format::Error::from)
+        .and_then(|file| {
+            stream::format_stream(path, io::BufWriter::new(file), &cfg.format, progress)
+        });
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err.into());
+        }
+    };
 
-fn parse_json_value(data: &str) -> Result<Value> {
-    match serde_json::from_str::<Value>(data) {
-        Ok(value) => Ok(value),
-        Err(_) => json5::from_str::<Value>(data)
-            .map_err(|err| CustomError::Custom(format!("Failed to parse JSON content: {err}"))),
+    if outcome.changed {
+        if cfg.backup {
+            let backup_path = path.with_extension("bak");
+            fs::copy(path, &backup_path)?;
+            info!("Backup created: {:?}", backup_path);
+        }
+        fs::rename(&temp_path, path)?;
+        info!("Sorted JSON written back to {:?}", path);
+    } else {
+        fs::remove_file(&temp_path)?;
     }
+
+    Ok(ProcessOutcome {
+        changed: outcome.changed,
+        output: None,
+        diff: None,
+        duplicates: outcome.duplicates,
+    })
 }
 
 fn is_quiet(cfg: &Configuration) -> bool {
     matches!(cfg.log_level, LogLevel::Quiet)
 }
 
-fn print_output(path: &PathBuf, output: &str, total_files: usize) {
-    if total_files > 1 {
-        println!("--- {} ---", path.display());
+fn print_duplicates(cfg: &Configuration, path: &str, duplicates: &[DuplicateKey]) {
+    if is_quiet(cfg) {
+        return;
     }
-    println!("{output}");
-}
-
-fn is_jsonc(path: &PathBuf) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.eq_ignore_ascii_case("jsonc"))
-        .unwrap_or(false)
-}
-
-fn restore_jsonc_leading_comments(original: &str, formatted: &str) -> String {
-    let mut comments_by_key = collect_leading_comments_by_key(original);
-    let mut result = Vec::new();
-
-    for line in formatted.lines() {
-        let trimmed = line.trim_start();
-        if let Some(key) = extract_object_key(trimmed) {
-            if let Some(queue) = comments_by_key.get_mut(&key) {
-                if let Some(comments) = queue.pop_front() {
-                    result.extend(comments);
-                }
-            }
-        }
-        result.push(line.to_string());
+    for duplicate in duplicates {
+        eprintln!("{} {path}: {duplicate}", "Warning:".yellow().bold());
     }
-
-    format!("{}\n", result.join("\n"))
 }
 
-fn collect_leading_comments_by_key(original: &str) -> HashMap<String, VecDeque<Vec<String>>> {
-    let mut by_key: HashMap<String, VecDeque<Vec<String>>> = HashMap::new();
-    let mut pending_comments: Vec<String> = Vec::new();
-    let mut in_block_comment = false;
-
-    for line in original.lines() {
-        let trimmed = line.trim_start();
-
-        if in_block_comment {
-            pending_comments.push(line.to_string());
-            if trimmed.contains("*/") {
-                in_block_comment = false;
-            }
-            continue;
-        }
-
-        if trimmed.starts_with("//") {
-            pending_comments.push(line.to_string());
-            continue;
-        }
-
-        if trimmed.starts_with("/*") {
-            pending_comments.push(line.to_string());
-            if !trimmed.contains("*/") {
-                in_block_comment = true;
-            }
-            continue;
-        }
-
-        if let Some(key) = extract_object_key(trimmed) {
-            if !pending_comments.is_empty() {
-                by_key
-                    .entry(key)
-                    .or_default()
-                    .push_back(std::mem::take(&mut pending_comments));
-            }
-            continue;
-        }
-
-        if !trimmed.is_empty() {
-            pending_comments.clear();
-        }
+fn print_diff(diff: &str) {
+    for line in diff.lines() {
+        let line = if line.starts_with("---") || line.starts_with("+++") {
+            line.bold()
+        } else if line.starts_with("@@") {
+            line.cyan()
+        } else if line.starts_with('-') {
+            line.red()
+        } else if line.starts_with('+') {
+            line.green()
+        } else {
+            line.normal()
+        };
+        println!("{line}");
     }
-
-    by_key
 }
 
-fn extract_object_key(trimmed: &str) -> Option<String> {
-    if !trimmed.starts_with('"') {
-        return None;
-    }
-
-    let mut escaped = false;
-    let mut end_index = None;
-    for (index, ch) in trimmed.char_indices().skip(1) {
-        if escaped {
-            escaped = false;
-            continue;
-        }
-        if ch == '\\' {
-            escaped = true;
-            continue;
-        }
-        if ch == '"' {
-            end_index = Some(index);
-            break;
-        }
-    }
-
-    let end_index = end_index?;
-    let remainder = trimmed[end_index + 1..].trim_start();
-    if !remainder.starts_with(':') {
-        return None;
+fn print_output(path: &Path, output: &str, total_files: usize) {
+    if total_files > 1 {
+        println!("--- {} ---", path.display());
     }
-
-    Some(trimmed[1..end_index].to_string())
+    print_document(output);
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_detect_indent() {
-        let json = r#"
-{
-    "key": "value",
-    "nested": {
-        "inner": "value"
-    }
-}"#;
-        assert_eq!(detect_indent(json), Some("    ".to_string()));
-
-        let json_tabs = r#"
-{
-	"key": "value",
-	"nested": {
-		"inner": "value"
-	}
-}"#;
-        assert_eq!(detect_indent(json_tabs), Some("\t".to_string()));
-
-        let json_no_indent = r#"{"key": "value"}"#;
-        assert_eq!(detect_indent(json_no_indent), None);
+/// Prints formatted output, adding a line break only if it lacks one.
+fn print_document(output: &str) {
+    if output.ends_with('\n') {
+        print!("{output}");
+    } else {
+        println!("{output}");
     }
 }
@@ -0,0 +1,458 @@
+use clap::ValueEnum;
+use std::path::Path;
+
+use crate::sort::{SortOptions, SortRule};
+use crate::SortOrder;
+
+/// A curated key order for a well-known file schema.
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum Preset {
+    /// Pick the preset from each file's name
+    #[clap(name = "auto")]
+    Auto,
+    #[clap(name = "package-json", alias = "package")]
+    PackageJson,
+    #[clap(name = "tsconfig", alias = "jsconfig")]
+    Tsconfig,
+    #[clap(name = "composer-json", alias = "composer")]
+    ComposerJson,
+    #[clap(name = "eslintrc")]
+    Eslintrc,
+    #[clap(name = "deno-json", alias = "deno")]
+    DenoJson,
+    #[clap(name = "manifest-json", alias = "manifest")]
+    ManifestJson,
+}
+
+const PACKAGE_JSON_KEYS: &[&str] = &[
+    "$schema",
+    "name",
+    "displayName",
+    "version",
+    "private",
+    "description",
+    "categories",
+    "keywords",
+    "homepage",
+    "bugs",
+    "repository",
+    "funding",
+    "license",
+    "qna",
+    "author",
+    "maintainers",
+    "contributors",
+    "publisher",
+    "sideEffects",
+    "type",
+    "imports",
+    "exports",
+    "main",
+    "svelte",
+    "umd:main",
+    "jsdelivr",
+    "unpkg",
+    "module",
+    "source",
+    "jsnext:main",
+    "browser",
+    "react-native",
+    "types",
+    "typesVersions",
+    "typings",
+    "style",
+    "example",
+    "examplestyle",
+    "assets",
+    "bin",
+    "man",
+    "directories",
+    "files",
+    "workspaces",
+    "binary",
+    "scripts",
+    "betterScripts",
+    "contributes",
+    "activationEvents",
+    "husky",
+    "simple-git-hooks",
+    "pre-commit",
+    "commitlint",
+    "lint-staged",
+    "config",
+    "nodemonConfig",
+    "browserify",
+    "babel",
+    "browserslist",
+    "xo",
+    "prettier",
+    "eslintConfig",
+    "eslintIgnore",
+    "npmpackagejsonlint",
+    "release",
+    "remarkConfig",
+    "stylelint",
+    "ava",
+    "jest",
+    "mocha",
+    "nyc",
+    "tap",
+    "resolutions",
+    "overrides",
+    "dependencies",
+    "devDependencies",
+    "dependenciesMeta",
+    "peerDependencies",
+    "peerDependenciesMeta",
+    "optionalDependencies",
+    "bundledDependencies",
+    "bundleDependencies",
+    "extensionPack",
+    "extensionDependencies",
+    "flat",
+    "packageManager",
+    "engines",
+    "engineStrict",
+    "volta",
+    "languageName",
+    "os",
+    "cpu",
+    "preferGlobal",
+    "publishConfig",
+    "icon",
+    "badges",
+    "galleryBanner",
+    "preview",
+    "markdown",
+    "pnpm",
+];
+
+const PACKAGE_JSON_SORTED: &[&str] = &[
+    "/bin",
+    "/dependencies",
+    "/dependenciesMeta",
+    "/devDependencies",
+    "/directories",
+    "/engines",
+    "/optionalDependencies",
+    "/peerDependencies",
+    "/peerDependenciesMeta",
+    "/publishConfig",
+    "/resolutions",
+    "/volta",
+];
+
+const TSCONFIG_KEYS: &[&str] = &[
+    "$schema",
+    "extends",
+    "compilerOptions",
+    "files",
+    "include",
+    "exclude",
+    "references",
+    "watchOptions",
+    "typeAcquisition",
+];
+
+const TSCONFIG_SORTED: &[&str] = &["/compilerOptions", "/watchOptions", "/typeAcquisition"];
+
+const COMPOSER_JSON_KEYS: &[&str] = &[
+    "$schema",
+    "name",
+    "type",
+    "description",
+    "keywords",
+    "homepage",
+    "readme",
+    "time",
+    "license",
+    "authors",
+    "support",
+    "funding",
+    "require",
+    "require-dev",
+    "conflict",
+    "replace",
+    "provide",
+    "suggest",
+    "autoload",
+    "autoload-dev",
+    "include-path",
+    "target-dir",
+    "minimum-stability",
+    "prefer-stable",
+    "repositories",
+    "config",
+    "scripts",
+    "scripts-descriptions",
+    "extra",
+    "bin",
+    "archive",
+    "abandoned",
+    "non-feature-branches",
+];
+
+const COMPOSER_JSON_SORTED: &[&str] = &["/conflict", "/replace", "/provide", "/suggest", "/config"];
+
+/// Composer lists the platform requirements before any packages.
+const COMPOSER_PLATFORM_PACKAGES: &[&str] = &["php", "php-64bit", "hhvm"];
+
+const ESLINTRC_KEYS: &[&str] = &[
+    "$schema",
+    "root",
+    "extends",
+    "parser",
+    "parserOptions",
+    "env",
+    "globals",
+    "plugins",
+    "processor",
+    "settings",
+    "rules",
+    "overrides",
+    "files",
+    "excludedFiles",
+    "ignorePatterns",
+    "noInlineConfig",
+    "reportUnusedDisableDirectives",
+];
+
+const ESLINTRC_SORTED: &[&str] = &[
+    "/env",
+    "/globals",
+    "/parserOptions",
+    "/rules",
+    "/overrides/*/env",
+    "/overrides/*/globals",
+    "/overrides/*/rules",
+];
+
+const DENO_JSON_KEYS: &[&str] = &[
+    "$schema",
+    "name",
+    "version",
+    "exports",
+    "workspace",
+    "imports",
+    "scopes",
+    "importMap",
+    "compilerOptions",
+    "tasks",
+    "lint",
+    "fmt",
+    "test",
+    "bench",
+    "publish",
+    "lock",
+    "nodeModulesDir",
+    "vendor",
+    "unstable",
+    "include",
+    "exclude",
+];
+
+const DENO_JSON_SORTED: &[&str] = &["/imports", "/scopes", "/compilerOptions"];
+
+const MANIFEST_JSON_KEYS: &[&str] = &[
+    "$schema",
+    "manifest_version",
+    "name",
+    "short_name",
+    "version",
+    "version_name",
+    "description",
+    "author",
+    "homepage_url",
+    "id",
+    "start_url",
+    "scope",
+    "display",
+    "orientation",
+    "lang",
+    "dir",
+    "theme_color",
+    "background_color",
+    "icons",
+    "screenshots",
+    "default_locale",
+    "minimum_chrome_version",
+    "browser_specific_settings",
+    "action",
+    "browser_action",
+    "page_action",
+    "background",
+    "content_scripts",
+    "content_security_policy",
+    "permissions",
+    "optional_permissions",
+    "host_permissions",
+    "optional_host_permissions",
+    "web_accessible_resources",
+    "commands",
+];
+
+const MANIFEST_JSON_SORTED: &[&str] = &[];
+
+impl Preset {
+    /// Picks the preset matching a file name, if any.
+    pub fn detect(path: &Path) -> Option<Preset> {
+        let name = path.file_name()?.to_str()?;
+        match name {
+            "package.json" => Some(Preset::PackageJson),
+            "composer.json" => Some(Preset::ComposerJson),
+            ".eslintrc.json" => Some(Preset::Eslintrc),
+            "deno.json" | "deno.jsonc" => Some(Preset::DenoJson),
+            "manifest.json" => Some(Preset::ManifestJson),
+            _ if (name.starts_with("tsconfig") || name.starts_with("jsconfig"))
+                && (name.ends_with(".json") || name.ends_with(".jsonc")) =>
+            {
+                Some(Preset::Tsconfig)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the preset to use for `path`, resolving [`Preset::Auto`] from
+    /// the file name.
+    pub fn resolve(&self, path: Option<&Path>) -> Option<Preset> {
+        match self {
+            Preset::Auto => path.and_then(Preset::detect),
+            preset => Some(preset.clone()),
+        }
+    }
+
+    /// Layers the preset under `options`: the preset's rules come first so
+    /// that rules from the user's configuration still win, and the user's
+    /// `first` and `last` keys take precedence over the preset's key order.
+    pub fn apply(&self, options: &SortOptions) -> SortOptions {
+        let mut rules = self.rules();
+        for rule in &mut rules {
+            if let Some(keys) = rule.first.take() {
+                let pinned =
+                    |key: &String| options.first.contains(key) || options.last.contains(key);
+                let mut first = options.first.clone();
+                first.extend(keys.into_iter().filter(|key| !pinned(key)));
+                rule.first = Some(first);
+            }
+        }
+        rules.extend(options.rules.iter().cloned());
+        SortOptions {
+            rules,
+            ..options.clone()
+        }
+    }
+
+    fn rules(&self) -> Vec<SortRule> {
+        let (keys, sorted) = match self {
+            Preset::Auto => return Vec::new(),
+            Preset::PackageJson => (PACKAGE_JSON_KEYS, PACKAGE_JSON_SORTED),
+            Preset::Tsconfig => (TSCONFIG_KEYS, TSCONFIG_SORTED),
+            Preset::ComposerJson => (COMPOSER_JSON_KEYS, COMPOSER_JSON_SORTED),
+            Preset::Eslintrc => (ESLINTRC_KEYS, ESLINTRC_SORTED),
+            Preset::DenoJson => (DENO_JSON_KEYS, DENO_JSON_SORTED),
+            Preset::ManifestJson => (MANIFEST_JSON_KEYS, MANIFEST_JSON_SORTED),
+        };
+
+        // Nested objects keep their order unless the preset lists them.
+        let mut rules = vec![
+            rule("/**", Some(SortOrder::Unsorted), None),
+            rule("", None, Some(keys)),
+        ];
+        rules.extend(sorted.iter().map(|path| rule(path, None, None)));
+        match self {
+            Preset::ComposerJson => rules.extend(
+                ["/require", "/require-dev"]
+                    .map(|path| rule(path, None, Some(COMPOSER_PLATFORM_PACKAGES))),
+            ),
+            Preset::ManifestJson => rules.push(rule("/icons", Some(SortOrder::NaturalAsc), None)),
+            _ => {}
+        }
+        rules
+    }
+}
+
+fn rule(path: &str, order: Option<SortOrder>, first: Option<&[&str]>) -> SortRule {
+    SortRule {
+        path: path.parse().expect("preset paths are valid selectors"),
+        order,
+        first: first.map(|keys| keys.iter().map(|key| key.to_string()).collect()),
+        ..SortRule::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::sort;
+    use serde_json::Value;
+
+    #[test]
+    fn test_detect_preset_from_file_name() {
+        assert_eq!(
+            Preset::detect(Path::new("./packages/a/package.json")),
+            Some(Preset::PackageJson)
+        );
+        assert_eq!(
+            Preset::detect(Path::new("tsconfig.build.json")),
+            Some(Preset::Tsconfig)
+        );
+        assert_eq!(
+            Preset::detect(Path::new(".eslintrc.json")),
+            Some(Preset::Eslintrc)
+        );
+        // Extensionless `.eslintrc` files may be YAML and are never formatted.
+        assert_eq!(Preset::detect(Path::new(".eslintrc")), None);
+        assert_eq!(Preset::detect(Path::new("data.json")), None);
+        assert_eq!(Preset::Auto.resolve(None), None);
+    }
+
+    #[test]
+    fn test_package_json_preset() {
+        let data = r#"
+        {
+            "scripts": { "test": "t", "build": "b" },
+            "dependencies": { "b": "1", "a": "1" },
+            "zzz": 1,
+            "exports": { "./b": "./b.js", ".": "./index.js" },
+            "version": "1.0.0",
+            "custom": 2,
+            "name": "pkg"
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let options = Preset::PackageJson.apply(&SortOptions::default());
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"name":"pkg","version":"1.0.0","exports":{"./b":"./b.js",".":"./index.js"},"scripts":{"test":"t","build":"b"},"dependencies":{"a":"1","b":"1"},"custom":2,"zzz":1}"#
+        );
+    }
+
+    #[test]
+    fn test_user_pins_win_over_preset_order() {
+        let json: Value = serde_json::from_str(
+            r#"{"version": "1.0.0", "foo": 1, "license": "MIT", "name": "pkg", "zzz": 2}"#,
+        )
+        .unwrap();
+        let options = SortOptions {
+            first: vec!["foo".to_string()],
+            last: vec!["name".to_string()],
+            ..SortOptions::default()
+        };
+        let options = Preset::PackageJson.apply(&options);
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"foo":1,"version":"1.0.0","license":"MIT","zzz":2,"name":"pkg"}"#
+        );
+    }
+
+    #[test]
+    fn test_composer_json_preset_keeps_platform_first() {
+        let json: Value =
+            serde_json::from_str(r#"{"require": {"monolog/monolog": "^3", "ext-json": "*", "php": ">=8.1"}, "name": "a/b"}"#)
+                .unwrap();
+        let options = Preset::ComposerJson.apply(&SortOptions::default());
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"name":"a/b","require":{"php":">=8.1","ext-json":"*","monolog/monolog":"^3"}}"#
+        );
+    }
+}
@@ -0,0 +1,660 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cst::{DuplicateKey, DuplicateKeysError};
+use crate::format;
+
+/// A machine-readable format for the results of a run.
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum Reporter {
+    /// One JSON document with every file and a summary
+    #[clap(name = "json")]
+    Json,
+    /// SARIF 2.1.0, for code scanning dashboards
+    #[clap(name = "sarif")]
+    Sarif,
+    /// JUnit XML, one test case per file
+    #[clap(name = "junit")]
+    Junit,
+    /// GitHub Actions workflow commands, shown as annotations
+    #[clap(name = "github")]
+    Github,
+    /// Code Climate JSON, for GitLab code quality reports
+    #[clap(name = "codeclimate")]
+    Codeclimate,
+}
+
+/// What happened to one file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileStatus {
+    /// The file was already formatted.
+    Formatted,
+    /// The file would change, but was not written.
+    NeedsFormatting,
+    /// The file changed and was written back.
+    Reformatted,
+    /// The file could not be formatted, for one reason or several (e.g. one
+    /// per repeated key).
+    Error(Vec<ReportError>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Position of the error in the file, for parse errors.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub duration: Duration,
+    /// Repeated keys that were reported without failing the file.
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+/// What went wrong with a file; each kind is its own SARIF rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    Io,
+    Parse,
+    DuplicateKey,
+    Canonical,
+    Stream,
+    EditorConfig,
+    /// Listing the files failed, e.g. on a bad pattern.
+    Walk,
+    Other,
+}
+
+impl ErrorKind {
+    const ALL: [ErrorKind; 8] = [
+        ErrorKind::Io,
+        ErrorKind::Parse,
+        ErrorKind::DuplicateKey,
+        ErrorKind::Canonical,
+        ErrorKind::Stream,
+        ErrorKind::EditorConfig,
+        ErrorKind::Walk,
+        ErrorKind::Other,
+    ];
+
+    /// The rule id reporters file errors of this kind under.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io-error",
+            ErrorKind::Parse => "parse-error",
+            ErrorKind::DuplicateKey => "duplicate-key",
+            ErrorKind::Canonical => "canonical-error",
+            ErrorKind::Stream => "stream-error",
+            ErrorKind::EditorConfig => "editorconfig-error",
+            ErrorKind::Walk => "walk-error",
+            ErrorKind::Other => "error",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            ErrorKind::Io => "File could not be read or written",
+            ErrorKind::Parse => "File could not be parsed",
+            ErrorKind::DuplicateKey => "Key repeated within an object",
+            ErrorKind::Canonical => "File has no canonical form",
+            ErrorKind::Stream => "File could not be streamed",
+            ErrorKind::EditorConfig => "File's .editorconfig could not be read",
+            ErrorKind::Walk => "Files could not be listed",
+            ErrorKind::Other => "File could not be formatted",
+        }
+    }
+}
+
+impl ReportError {
+    /// The errors `err` stands for: one per repeated key, otherwise one.
+    pub fn from_error(err: &format::Error) -> Vec<ReportError> {
+        let kind = match err {
+            format::Error::Parse(err) => {
+                return vec![ReportError {
+                    kind: ErrorKind::Parse,
+                    message: err.message.clone(),
+                    line: Some(err.line),
+                    column: Some(err.column),
+                }]
+            }
+            format::Error::DuplicateKeys(DuplicateKeysError(duplicates)) => {
+                return duplicates.iter().map(ReportError::from).collect()
+            }
+            format::Error::Io(_) => ErrorKind::Io,
+            format::Error::Pattern(_) | format::Error::Walk(_) => ErrorKind::Walk,
+            format::Error::EditorConfig(_) => ErrorKind::EditorConfig,
+            format::Error::Canonical(_) => ErrorKind::Canonical,
+            format::Error::Stream(_) => ErrorKind::Stream,
+        };
+        vec![ReportError::new(kind, err.to_string())]
+    }
+
+    /// An error without a position in the file.
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        ReportError {
+            kind,
+            message,
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl From<&DuplicateKey> for ReportError {
+    fn from(duplicate: &DuplicateKey) -> Self {
+        ReportError {
+            kind: ErrorKind::DuplicateKey,
+            message: format!("duplicate key `{}`", duplicate.name),
+            line: Some(duplicate.line),
+            column: Some(duplicate.column),
+        }
+    }
+}
+
+impl FileStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            FileStatus::Formatted => "formatted",
+            FileStatus::NeedsFormatting => "needs-formatting",
+            FileStatus::Reformatted => "reformatted",
+            FileStatus::Error(_) => "error",
+        }
+    }
+}
+
+impl Reporter {
+    /// Renders the results of a run that took `total` in this format.
+    pub fn render(&self, files: &[FileReport], total: Duration) -> String {
+        match self {
+            Reporter::Json => render_json(files, total),
+            Reporter::Sarif => render_sarif(files),
+            Reporter::Junit => render_junit(files, total),
+            Reporter::Github => render_github(files),
+            Reporter::Codeclimate => render_codeclimate(files),
+        }
+    }
+}
+
+fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn count(files: &[FileReport], status: fn(&FileStatus) -> bool) -> usize {
+    files.iter().filter(|file| status(&file.status)).count()
+}
+
+fn render_json(files: &[FileReport], total: Duration) -> String {
+    let entries: Vec<Value> = files
+        .iter()
+        .map(|file| {
+            let mut entry = json!({
+                "path": display_path(&file.path),
+                "status": file.status.name(),
+                "duration_ms": millis(file.duration),
+            });
+            if let FileStatus::Error(errors) = &file.status {
+                let errors: Vec<Value> = errors
+                    .iter()
+                    .map(|error| {
+                        json!({
+                            "message": error.message,
+                            "line": error.line,
+                            "column": error.column,
+                        })
+                    })
+                    .collect();
+                // `error` is the first one, as before files could have several.
+                entry["error"] = errors.first().cloned().unwrap_or_default();
+                entry["errors"] = errors.into();
+            }
+            if !file.duplicates.is_empty() {
+                entry["duplicates"] = file
+                    .duplicates
+                    .iter()
+                    .map(|duplicate| {
+                        json!({
+                            "name": duplicate.name,
+                            "line": duplicate.line,
+                            "column": duplicate.column,
+                        })
+                    })
+                    .collect();
+            }
+            entry
+        })
+        .collect();
+
+    let report = json!({
+        "files": entries,
+        "summary": {
+            "total": files.len(),
+            "formatted": count(files, |s| matches!(s, FileStatus::Formatted)),
+            "needs_formatting": count(files, |s| matches!(s, FileStatus::NeedsFormatting)),
+            "reformatted": count(files, |s| matches!(s, FileStatus::Reformatted)),
+            "errors": count(files, |s| matches!(s, FileStatus::Error(_))),
+            "duration_ms": millis(total),
+        },
+    });
+    serde_json::to_string_pretty(&report).expect("reports always serialize")
+}
+
+fn render_sarif(files: &[FileReport]) -> String {
+    let result = |uri: &str, level: &str, error: &ReportError| {
+        let mut location = json!({ "artifactLocation": { "uri": uri } });
+        if let Some(line) = error.line {
+            location["region"] = json!({
+                "startLine": line,
+                "startColumn": error.column,
+            });
+        }
+        json!({
+            "ruleId": error.kind.rule(),
+            "level": level,
+            "message": { "text": error.message },
+            "locations": [{ "physicalLocation": location }],
+        })
+    };
+
+    let results: Vec<Value> = files
+        .iter()
+        .flat_map(|file| {
+            let uri = display_path(&file.path);
+            let mut results = match &file.status {
+                FileStatus::NeedsFormatting => vec![json!({
+                    "ruleId": "needs-formatting",
+                    "level": "warning",
+                    "message": { "text": "File is not formatted" },
+                    "locations": [{
+                        "physicalLocation": { "artifactLocation": { "uri": uri } },
+                    }],
+                })],
+                FileStatus::Error(errors) => errors
+                    .iter()
+                    .map(|error| result(&uri, "error", error))
+                    .collect(),
+                FileStatus::Formatted | FileStatus::Reformatted => Vec::new(),
+            };
+            results.extend(
+                file.duplicates
+                    .iter()
+                    .map(|duplicate| result(&uri, "warning", &duplicate.into())),
+            );
+            results
+        })
+        .collect();
+
+    let mut rules = vec![json!({
+        "id": "needs-formatting",
+        "shortDescription": { "text": "File is not formatted" },
+    })];
+    rules.extend(ErrorKind::ALL.iter().map(|kind| {
+        json!({
+            "id": kind.rule(),
+            "shortDescription": { "text": kind.description() },
+        })
+    }));
+
+    let report = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "tidy-json",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&report).expect("reports always serialize")
+}
+
+fn render_junit(files: &[FileReport], total: Duration) -> String {
+    let failures = count(files, |s| matches!(s, FileStatus::NeedsFormatting));
+    let errors = count(files, |s| matches!(s, FileStatus::Error(_)));
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"tidy-json\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{:.3}\">\n",
+        files.len(),
+        total.as_secs_f64()
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"tidy-json\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{:.3}\">\n",
+        files.len(),
+        total.as_secs_f64()
+    ));
+    for file in files {
+        let name = xml_escape(&display_path(&file.path));
+        let time = file.duration.as_secs_f64();
+        let mut body = match &file.status {
+            FileStatus::Formatted | FileStatus::Reformatted => String::new(),
+            FileStatus::NeedsFormatting => {
+                "      <failure message=\"File is not formatted\"/>\n".to_string()
+            }
+            FileStatus::Error(errors) => {
+                let message = errors.iter().map(describe).collect::<Vec<_>>().join("; ");
+                format!("      <error message=\"{}\"/>\n", xml_escape(&message))
+            }
+        };
+        if !file.duplicates.is_empty() {
+            let warnings: Vec<_> = file.duplicates.iter().map(ToString::to_string).collect();
+            body.push_str(&format!(
+                "      <system-err>{}</system-err>\n",
+                xml_escape(&warnings.join("\n"))
+            ));
+        }
+        if body.is_empty() {
+            out.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"tidy-json\" time=\"{time:.3}\"/>\n"
+            ));
+        } else {
+            out.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"tidy-json\" time=\"{time:.3}\">\n{body}    </testcase>\n"
+            ));
+        }
+    }
+    out.push_str("  </testsuite>\n</testsuites>");
+    out
+}
+
+/// An error's message with its position, if it has one.
+fn describe(error: &ReportError) -> String {
+    match (error.line, error.column) {
+        (Some(line), Some(column)) => format!("{} at line {line} column {column}", error.message),
+        _ => error.message.clone(),
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn render_github(files: &[FileReport]) -> String {
+    let annotation = |command: &str, path: &str, error: &ReportError| {
+        let mut properties = format!("file={path}");
+        if let Some(line) = error.line {
+            properties.push_str(&format!(",line={line}"));
+        }
+        if let Some(column) = error.column {
+            properties.push_str(&format!(",col={column}"));
+        }
+        format!(
+            "::{command} {properties},title=tidy-json::{}",
+            github_escape_data(&error.message)
+        )
+    };
+
+    files
+        .iter()
+        .flat_map(|file| {
+            let path = github_escape_property(&display_path(&file.path));
+            let mut lines = match &file.status {
+                FileStatus::NeedsFormatting => vec![format!(
+                    "::warning file={path},title=tidy-json::File is not formatted"
+                )],
+                FileStatus::Error(errors) => errors
+                    .iter()
+                    .map(|error| annotation("error", &path, error))
+                    .collect(),
+                FileStatus::Formatted | FileStatus::Reformatted => Vec::new(),
+            };
+            lines.extend(
+                file.duplicates
+                    .iter()
+                    .map(|duplicate| annotation("warning", &path, &duplicate.into())),
+            );
+            lines
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn github_escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn github_escape_property(value: &str) -> String {
+    github_escape_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn render_codeclimate(files: &[FileReport]) -> String {
+    let issue = |path: &str, check: &str, severity: &str, error: &ReportError| {
+        let line = error.line.unwrap_or(1);
+        let fingerprint = fingerprint(&[
+            path,
+            check,
+            &error.message,
+            &line.to_string(),
+            &error.column.unwrap_or_default().to_string(),
+        ]);
+        json!({
+            "type": "issue",
+            "check_name": check,
+            "description": error.message,
+            "categories": ["Style"],
+            "severity": severity,
+            "fingerprint": fingerprint,
+            "location": { "path": path, "lines": { "begin": line } },
+        })
+    };
+
+    let issues: Vec<Value> = files
+        .iter()
+        .flat_map(|file| {
+            let path = display_path(&file.path);
+            let mut issues = match &file.status {
+                FileStatus::NeedsFormatting => vec![issue(
+                    &path,
+                    "needs-formatting",
+                    "minor",
+                    &ReportError::new(ErrorKind::Other, "File is not formatted".to_string()),
+                )],
+                FileStatus::Error(errors) => errors
+                    .iter()
+                    .map(|error| issue(&path, error.kind.rule(), "major", error))
+                    .collect(),
+                FileStatus::Formatted | FileStatus::Reformatted => Vec::new(),
+            };
+            issues.extend(file.duplicates.iter().map(|duplicate| {
+                issue(
+                    &path,
+                    ErrorKind::DuplicateKey.rule(),
+                    "minor",
+                    &duplicate.into(),
+                )
+            }));
+            issues
+        })
+        .collect();
+    serde_json::to_string_pretty(&issues).expect("reports always serialize")
+}
+
+/// A stable 64-bit FNV-1a hash of `parts`, so that an issue keeps its
+/// fingerprint from one run to the next.
+fn fingerprint(parts: &[&str]) -> String {
+    let hash = parts.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, part| {
+        part.bytes().chain([0]).fold(hash, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3)
+        })
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reports() -> Vec<FileReport> {
+        vec![
+            FileReport {
+                path: PathBuf::from("./a.json"),
+                status: FileStatus::Formatted,
+                duration: Duration::from_millis(1),
+                duplicates: vec![DuplicateKey {
+                    name: "id".to_string(),
+                    line: 4,
+                    column: 5,
+                }],
+            },
+            FileReport {
+                path: PathBuf::from("./b.json"),
+                status: FileStatus::NeedsFormatting,
+                duration: Duration::from_millis(2),
+                duplicates: Vec::new(),
+            },
+            FileReport {
+                path: PathBuf::from("./c.json"),
+                status: FileStatus::Error(vec![ReportError {
+                    kind: ErrorKind::Parse,
+                    message: "expected value".to_string(),
+                    line: Some(3),
+                    column: Some(7),
+                }]),
+                duration: Duration::from_millis(3),
+                duplicates: Vec::new(),
+            },
+            FileReport {
+                path: PathBuf::from("./d.json"),
+                status: FileStatus::Error(ReportError::from_error(&format::Error::DuplicateKeys(
+                    DuplicateKeysError(vec![
+                        DuplicateKey {
+                            name: "a".to_string(),
+                            line: 2,
+                            column: 3,
+                        },
+                        DuplicateKey {
+                            name: "b".to_string(),
+                            line: 5,
+                            column: 3,
+                        },
+                    ]),
+                ))),
+                duration: Duration::from_millis(4),
+                duplicates: Vec::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json_report() {
+        let report: Value =
+            serde_json::from_str(&Reporter::Json.render(&reports(), Duration::from_millis(6)))
+                .unwrap();
+        assert_eq!(report["files"][1]["path"], "b.json");
+        assert_eq!(report["files"][1]["status"], "needs-formatting");
+        assert_eq!(report["files"][0]["duplicates"][0]["name"], "id");
+        assert_eq!(report["files"][2]["error"]["line"], 3);
+        assert_eq!(report["files"][3]["error"]["message"], "duplicate key `a`");
+        assert_eq!(
+            report["files"][3]["errors"][1]["message"],
+            "duplicate key `b`"
+        );
+        assert_eq!(report["summary"]["errors"], 2);
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let report: Value =
+            serde_json::from_str(&Reporter::Sarif.render(&reports(), Duration::ZERO)).unwrap();
+        let results = report["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0]["ruleId"], "duplicate-key");
+        assert_eq!(results[0]["level"], "warning");
+        assert_eq!(results[2]["ruleId"], "parse-error");
+        assert_eq!(results[3]["ruleId"], "duplicate-key");
+        assert_eq!(results[3]["level"], "error");
+        assert_eq!(
+            results[2]["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            7
+        );
+        assert_eq!(
+            results[4]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            5
+        );
+
+        let rules = report["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap();
+        assert_eq!(rules.len(), 9);
+        let io = ReportError::from_error(&format::Error::Io(std::io::Error::other("denied")));
+        let stream = ReportError::from_error(&format::Error::Stream("canonical forms".to_string()));
+        assert_eq!(io[0].kind.rule(), "io-error");
+        assert_eq!(stream[0].kind.rule(), "stream-error");
+        assert!(rules.iter().any(|rule| rule["id"] == "stream-error"));
+    }
+
+    #[test]
+    fn test_codeclimate_report() {
+        let report: Value =
+            serde_json::from_str(&Reporter::Codeclimate.render(&reports(), Duration::ZERO))
+                .unwrap();
+        let issues = report.as_array().unwrap();
+        assert_eq!(issues.len(), 5);
+        assert_eq!(issues[0]["check_name"], "duplicate-key");
+        assert_eq!(issues[0]["severity"], "minor");
+        assert_eq!(issues[0]["location"]["path"], "a.json");
+        assert_eq!(issues[0]["location"]["lines"]["begin"], 4);
+        assert_eq!(issues[1]["check_name"], "needs-formatting");
+        assert_eq!(issues[1]["location"]["lines"]["begin"], 1);
+        assert_eq!(issues[2]["check_name"], "parse-error");
+        assert_eq!(issues[2]["severity"], "major");
+        assert_eq!(issues[2]["description"], "expected value");
+
+        let fingerprints: std::collections::HashSet<_> = issues
+            .iter()
+            .map(|issue| issue["fingerprint"].clone())
+            .collect();
+        assert_eq!(fingerprints.len(), 5);
+        assert_eq!(
+            Reporter::Codeclimate.render(&reports(), Duration::ZERO),
+            Reporter::Codeclimate.render(&reports(), Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_junit_and_github_reports() {
+        let junit = Reporter::Junit.render(&reports(), Duration::ZERO);
+        assert!(junit.contains("tests=\"4\" failures=\"1\" errors=\"2\""));
+        assert!(junit.contains("<error message=\"expected value at line 3 column 7\"/>"));
+        assert!(junit.contains("<error message=\"duplicate key `a` at line 2 column 3; duplicate key `b` at line 5 column 3\"/>"));
+        assert!(junit.contains("<system-err>duplicate key `id` at line 4 column 5</system-err>"));
+
+        assert_eq!(
+            Reporter::Github.render(&reports(), Duration::ZERO),
+            [
+                "::warning file=a.json,line=4,col=5,title=tidy-json::duplicate key `id`",
+                "::warning file=b.json,title=tidy-json::File is not formatted",
+                "::error file=c.json,line=3,col=7,title=tidy-json::expected value",
+                "::error file=d.json,line=2,col=3,title=tidy-json::duplicate key `a`",
+                "::error file=d.json,line=5,col=3,title=tidy-json::duplicate key `b`",
+            ]
+            .join("\n")
+        );
+    }
+}
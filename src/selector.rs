@@ -0,0 +1,129 @@
+use glob::Pattern;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("invalid path selector `{selector}`: {reason}")]
+pub struct SelectorError {
+    pub selector: String,
+    pub reason: String,
+}
+
+/// A JSON Pointer whose segments may be glob patterns, e.g.
+/// `/compilerOptions/paths`, `/dependencies/@types*` or `/**/items`. A `**`
+/// segment matches any number of segments and the empty pointer matches the
+/// root. Array elements are addressed by their index.
+#[derive(Debug, Clone, Default)]
+pub struct Selector {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Any,
+    Key(String),
+    Pattern(Pattern),
+}
+
+impl Segment {
+    fn matches(&self, key: &str) -> bool {
+        match self {
+            Segment::Any => true,
+            Segment::Key(expected) => expected == key,
+            Segment::Pattern(pattern) => pattern.matches(key),
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        if selector.is_empty() {
+            return Ok(Selector {
+                segments: Vec::new(),
+            });
+        }
+
+        let rest = selector.strip_prefix('/').ok_or_else(|| SelectorError {
+            selector: selector.to_string(),
+            reason: "must be empty or start with `/`".to_string(),
+        })?;
+
+        let segments = rest
+            .split('/')
+            .map(|raw| {
+                if raw == "**" {
+                    return Ok(Segment::Any);
+                }
+                let key = raw.replace("~1", "/").replace("~0", "~");
+                if key.contains(['*', '?', '[']) {
+                    Pattern::new(&key)
+                        .map(Segment::Pattern)
+                        .map_err(|err| SelectorError {
+                            selector: selector.to_string(),
+                            reason: err.to_string(),
+                        })
+                } else {
+                    Ok(Segment::Key(key))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Selector { segments })
+    }
+}
+
+impl Selector {
+    pub fn matches<S: AsRef<str>>(&self, path: &[S]) -> bool {
+        matches_segments(&self.segments, path)
+    }
+}
+
+fn matches_segments<S: AsRef<str>>(segments: &[Segment], path: &[S]) -> bool {
+    match segments.split_first() {
+        None => path.is_empty(),
+        Some((Segment::Any, rest)) => {
+            (0..=path.len()).any(|skip| matches_segments(rest, &path[skip..]))
+        }
+        Some((segment, rest)) => path.split_first().is_some_and(|(key, path)| {
+            segment.matches(key.as_ref()) && matches_segments(rest, path)
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(selector: &str, path: &[&str]) -> bool {
+        selector.parse::<Selector>().unwrap().matches(path)
+    }
+
+    #[test]
+    fn test_selector_matches() {
+        assert!(matches("", &[]));
+        assert!(!matches("", &["a"]));
+        assert!(matches(
+            "/compilerOptions/paths",
+            &["compilerOptions", "paths"]
+        ));
+        assert!(!matches("/compilerOptions", &["compilerOptions", "paths"]));
+        assert!(matches("/a~1b/c~0d", &["a/b", "c~d"]));
+        assert!(matches("/items/*/tags", &["items", "3", "tags"]));
+        assert!(matches(
+            "/dependencies/@types*",
+            &["dependencies", "@types/node"]
+        ));
+        assert!(matches("/**", &[]));
+        assert!(matches("/**/paths", &["compilerOptions", "paths"]));
+        assert!(matches("/**/paths", &["paths"]));
+        assert!(!matches("/**/paths", &["paths", "x"]));
+    }
+
+    #[test]
+    fn test_selector_rejects_invalid_input() {
+        assert!("dependencies".parse::<Selector>().is_err());
+        assert!("/[".parse::<Selector>().is_err());
+    }
+}
@@ -1,57 +1,621 @@
+use icu_collator::{Collator, CollatorOptions};
+use icu_locid::{LanguageIdentifier, Locale};
+use icu_locid_transform::{LocaleExpander, TransformResult};
+use rand::rngs::Xoshiro256PlusPlus;
 use rand::seq::SliceRandom;
-use std::collections::BTreeMap;
+use rand::SeedableRng;
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
 
 use serde_json::Value;
 
-use crate::SortOrder;
-
-pub fn sort(
-    value: &Value,
-    order: &SortOrder,
-    current_depth: u32,
-    desired_depth: Option<u32>,
-) -> Value {
-    if let Some(desired_depth) = desired_depth {
-        if current_depth == desired_depth {
-            return value.clone();
+use crate::cst::PrintOptions;
+use crate::selector::Selector;
+use crate::{ArrayOrder, Collation, SortOrder};
+
+/// Settings for [`sort`]. Start from [`SortOptions::new`] and set the fields
+/// that differ.
+#[derive(Debug, Clone, Default)]
+pub struct SortOptions {
+    pub order: SortOrder,
+    /// How many levels deep to sort; `None` sorts the whole tree.
+    pub depth: Option<u32>,
+    /// Keys placed before all others, in the given order.
+    pub first: Vec<String>,
+    /// Keys placed after all others, in the given order.
+    pub last: Vec<String>,
+    /// How keys are compared by the alphabetical and natural orders.
+    pub collation: Collation,
+    /// Locale used by [`Collation::Unicode`]; the root locale when unset.
+    pub locale: Option<Locale>,
+    /// Reorders array elements by value; arrays keep their order when unset.
+    pub sort_arrays: Option<ArrayOrder>,
+    /// Compares arrays of objects by the value of this member.
+    pub sort_arrays_by: Option<String>,
+    /// Removes repeated elements from arrays, keeping the first occurrence.
+    /// Numbers repeat when their exact values match, e.g. `1`, `1.0` and `1e0`.
+    pub dedupe_arrays: bool,
+    /// For the value orders, compares objects by this member; a JSON Pointer
+    /// such as `/meta/priority` reaches into nested objects.
+    pub order_by: Option<String>,
+    /// How the output is written, which [`SortOrder::LineLength`] needs to
+    /// measure lines; the formatter sets it from its own settings.
+    pub(crate) print: PrintOptions,
+    /// Seeds [`SortOrder::Random`] so that the same input and seed always
+    /// shuffle the same way; a fresh seed is drawn for each call when unset.
+    pub seed: Option<u64>,
+    /// Per-path overrides; the last rule matching a value applies to it.
+    pub rules: Vec<SortRule>,
+}
+
+/// Overrides the settings for the objects and arrays matching `path`.
+#[derive(Debug, Clone, Default)]
+pub struct SortRule {
+    pub path: Selector,
+    pub order: Option<SortOrder>,
+    pub order_by: Option<String>,
+    pub first: Option<Vec<String>>,
+    pub last: Option<Vec<String>>,
+    pub sort_arrays: Option<ArrayOrder>,
+    pub sort_arrays_by: Option<String>,
+    pub dedupe_arrays: Option<bool>,
+}
+
+impl SortOptions {
+    pub fn new(order: SortOrder) -> Self {
+        Self {
+            order,
+            ..Default::default()
         }
     }
+}
 
-    match value {
-        Value::Object(map) => {
-            let sorted_map: BTreeMap<_, _> = map.iter().collect();
-            let mut entries: Vec<_> = sorted_map.into_iter().collect();
-
-            match order {
-                SortOrder::AlphabeticalAsc => entries.sort_by(|(a, _), (b, _)| a.cmp(b)),
-                SortOrder::AlphabeticalDesc => entries.sort_by(|(a, _), (b, _)| b.cmp(a)),
-                SortOrder::KeyLengthAsc => entries.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len())),
-                SortOrder::KeyLengthDesc => entries.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len())),
-                SortOrder::LineLength => {
-                    entries.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+/// Where a key is pinned: before (0), among (1) or after (2) the sorted keys,
+/// along with its position in the pinned list.
+fn pin_rank(first: &[String], last: &[String], key: &str) -> (u8, usize) {
+    if let Some(index) = first.iter().position(|k| k == key) {
+        (0, index)
+    } else if let Some(index) = last.iter().position(|k| k == key) {
+        (2, index)
+    } else {
+        (1, 0)
+    }
+}
+
+/// A JSON tree whose object members and array elements can be reordered by
+/// [`sort`].
+pub trait Sortable: Clone {
+    type Member;
+    type Item;
+
+    /// Removes and returns the members if this is an object.
+    fn take_members(&mut self) -> Option<Vec<Self::Member>>;
+    fn set_members(&mut self, members: Vec<Self::Member>);
+    fn member_key(member: &Self::Member) -> &str;
+    /// Renders the first line of `member` as the formatter writes it
+    /// `level` levels deep, including the indentation but not the comma.
+    fn member_line(member: &Self::Member, options: &PrintOptions, level: usize) -> String;
+    fn member_value(member: &Self::Member) -> &Self;
+    fn member_value_mut(member: &mut Self::Member) -> &mut Self;
+    /// Removes and returns the elements if this is an array.
+    fn take_items(&mut self) -> Option<Vec<Self::Item>>;
+    fn set_items(&mut self, items: Vec<Self::Item>);
+    fn item_value(item: &Self::Item) -> &Self;
+    fn item_value_mut(item: &mut Self::Item) -> &mut Self;
+    /// The plain JSON value, used when comparing elements by value.
+    fn to_value(&self) -> Cow<'_, Value>;
+    /// A key that two elements share exactly when `dedupe_arrays` treats
+    /// them as repeats.
+    fn dedupe_key(&self) -> String;
+}
+
+impl Sortable for Value {
+    type Member = (String, Value);
+    type Item = Value;
+
+    fn take_members(&mut self) -> Option<Vec<Self::Member>> {
+        match self {
+            Value::Object(map) => Some(std::mem::take(map).into_iter().collect()),
+            _ => None,
+        }
+    }
+
+    fn set_members(&mut self, members: Vec<Self::Member>) {
+        *self = Value::Object(members.into_iter().collect());
+    }
+
+    fn member_key(member: &Self::Member) -> &str {
+        &member.0
+    }
+
+    fn member_line((key, value): &Self::Member, options: &PrintOptions, level: usize) -> String {
+        // Only nested lines depend on the indent, so the default one will do.
+        let rendered = serde_json::to_string_pretty(value).expect("values always serialize");
+        let first = rendered.lines().next().unwrap_or_default();
+        format!(
+            "{}{}: {first}",
+            options.indent.repeat(level),
+            Value::from(key.as_str())
+        )
+    }
+
+    fn member_value(member: &Self::Member) -> &Self {
+        &member.1
+    }
+
+    fn member_value_mut(member: &mut Self::Member) -> &mut Self {
+        &mut member.1
+    }
+
+    fn take_items(&mut self) -> Option<Vec<Self::Item>> {
+        match self {
+            Value::Array(arr) => Some(std::mem::take(arr)),
+            _ => None,
+        }
+    }
+
+    fn set_items(&mut self, items: Vec<Self::Item>) {
+        *self = Value::Array(items);
+    }
+
+    fn item_value(item: &Self::Item) -> &Self {
+        item
+    }
+
+    fn item_value_mut(item: &mut Self::Item) -> &mut Self {
+        item
+    }
+
+    fn to_value(&self) -> Cow<'_, Value> {
+        Cow::Borrowed(self)
+    }
+
+    fn dedupe_key(&self) -> String {
+        match self {
+            Value::Number(number) => number_key(&number.to_string()),
+            Value::Array(items) => array_key(items.iter().map(Value::dedupe_key)),
+            Value::Object(map) => object_key(
+                map.iter()
+                    .map(|(key, value)| (key.as_str(), value.dedupe_key())),
+            ),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Writes a number so that tokens with the same exact value, such as `1`,
+/// `1.0` and `1e0`, share a key without being rounded through `f64`.
+/// JSON5 hexadecimal numbers are keyed by their value, `Infinity` and `NaN`
+/// by their sign and name.
+pub(crate) fn number_key(raw: &str) -> String {
+    let (sign, unsigned) = match raw.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", raw.strip_prefix('+').unwrap_or(raw)),
+    };
+    if let Some(hex) = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))
+    {
+        return match u128::from_str_radix(hex, 16) {
+            Ok(value) => number_key(&format!("{sign}{value}")),
+            Err(_) => raw.to_string(),
+        };
+    }
+
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent),
+        None => (unsigned, "0"),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let is_decimal = !(integer.is_empty() && fraction.is_empty())
+        && integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit());
+    let (true, Ok(exponent)) = (is_decimal, exponent.parse::<i64>()) else {
+        return format!("{sign}{unsigned}");
+    };
+
+    let digits = format!("{integer}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+    if significant.is_empty() {
+        return "0".to_string();
+    }
+    let exponent = exponent
+        .saturating_sub(fraction.len() as i64)
+        .saturating_add((digits.len() - significant.len()) as i64);
+    format!("{sign}{significant}e{exponent}")
+}
+
+/// Joins the keys of array elements, in order.
+pub(crate) fn array_key(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+/// Joins the keys of object members regardless of their order, as objects
+/// with the same members in a different order are repeats too.
+pub(crate) fn object_key<'k>(members: impl Iterator<Item = (&'k str, String)>) -> String {
+    let mut members: Vec<_> = members
+        .map(|(key, value)| format!("{}:{value}", Value::from(key)))
+        .collect();
+    members.sort();
+    format!("{{{}}}", members.join(","))
+}
+
+/// Checks that [`Collation::Unicode`] can collate for `locale`. Languages
+/// CLDR does not know, such as a misspelled `dee`, are rejected instead of
+/// silently falling back to the root collation.
+pub fn check_locale(locale: &Locale) -> Result<(), String> {
+    let mut language = LanguageIdentifier::from(locale.id.language);
+    if !language.language.is_empty()
+        && LocaleExpander::new().maximize(&mut language) == TransformResult::Unmodified
+    {
+        return Err(format!("unknown language `{}`", locale.id.language));
+    }
+    Collator::try_new(&locale.into(), CollatorOptions::new())
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+/// Compares keys according to the configured [`Collation`].
+enum KeyCollator {
+    Binary,
+    CaseInsensitive,
+    LowerFirst,
+    UpperFirst,
+    Unicode(Box<Collator>),
+    Utf16,
+}
+
+impl KeyCollator {
+    fn new(options: &SortOptions) -> Self {
+        match options.collation {
+            Collation::Binary => Self::Binary,
+            Collation::CaseInsensitive => Self::CaseInsensitive,
+            Collation::LowerFirst => Self::LowerFirst,
+            Collation::UpperFirst => Self::UpperFirst,
+            Collation::Unicode => {
+                let locale = options.locale.clone().unwrap_or_default();
+                let collator = Collator::try_new(&(&locale).into(), CollatorOptions::new())
+                    .expect("compiled collation data falls back to the root locale");
+                Self::Unicode(Box::new(collator))
+            }
+            Collation::Utf16 => Self::Utf16,
+        }
+    }
+
+    fn compare(&self, a: &str, b: &str) -> Ordering {
+        let caseless = |value: &str| {
+            value
+                .chars()
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        };
+        let ordering = match self {
+            Self::Binary => Ordering::Equal,
+            Self::CaseInsensitive => caseless(a).cmp(&caseless(b)),
+            Self::LowerFirst => caseless(a).cmp(&caseless(b)).then_with(|| {
+                let upper = |value: &str| value.chars().map(char::is_uppercase).collect::<Vec<_>>();
+                upper(a).cmp(&upper(b))
+            }),
+            Self::UpperFirst => caseless(a).cmp(&caseless(b)).then_with(|| {
+                let lower = |value: &str| value.chars().map(char::is_lowercase).collect::<Vec<_>>();
+                lower(a).cmp(&lower(b))
+            }),
+            Self::Unicode(collator) => collator.compare(a, b),
+            Self::Utf16 => a.encode_utf16().cmp(b.encode_utf16()),
+        };
+        ordering.then_with(|| a.cmp(b))
+    }
+}
+
+pub fn sort<T: Sortable>(value: &T, options: &SortOptions) -> T {
+    sort_at(value, options, &[], &mut random_source(options))
+}
+
+/// The generator [`SortOrder::Random`] draws from, seeded from `options`.
+pub(crate) fn random_source(options: &SortOptions) -> Xoshiro256PlusPlus {
+    Xoshiro256PlusPlus::seed_from_u64(options.seed.unwrap_or_else(rand::random))
+}
+
+/// Sorts `value` as [`sort`] would if it sat at `path` inside a larger
+/// document, e.g. one record of a file that is streamed. Sorting every part
+/// with the same `rng`, in document order, shuffles them as [`sort`] would.
+pub(crate) fn sort_at<T: Sortable>(
+    value: &T,
+    options: &SortOptions,
+    path: &[String],
+    rng: &mut Xoshiro256PlusPlus,
+) -> T {
+    let mut sorted = value.clone();
+    let mut sorter = Sorter {
+        options,
+        collator: KeyCollator::new(options),
+        path: path.to_vec(),
+        rng,
+    };
+    sorter.sort(&mut sorted, path.len() as u32);
+    sorted
+}
+
+struct Sorter<'a> {
+    options: &'a SortOptions,
+    collator: KeyCollator,
+    /// Path of the value being sorted; only tracked when there are rules.
+    path: Vec<String>,
+    /// Shared by every object so one seed determines the whole output.
+    rng: &'a mut Xoshiro256PlusPlus,
+}
+
+impl<'a> Sorter<'a> {
+    fn rule(&self) -> Option<&'a SortRule> {
+        self.options
+            .rules
+            .iter()
+            .rev()
+            .find(|rule| rule.path.matches(&self.path))
+    }
+
+    fn sort<T: Sortable>(&mut self, value: &mut T, current_depth: u32) {
+        if let Some(desired_depth) = self.options.depth {
+            if current_depth == desired_depth {
+                return;
+            }
+        }
+
+        let tracking = !self.options.rules.is_empty();
+        let options = self.options;
+        let rule = self.rule();
+
+        if let Some(mut items) = value.take_items() {
+            for (index, item) in items.iter_mut().enumerate() {
+                if tracking {
+                    self.path.push(index.to_string());
                 }
-                SortOrder::Random => {
-                    let mut rng = rand::rng();
-                    entries.shuffle(&mut rng);
+                self.sort(T::item_value_mut(item), current_depth + 1);
+                if tracking {
+                    self.path.pop();
                 }
             }
 
-            Value::Object(
-                entries
+            let dedupe = rule
+                .and_then(|r| r.dedupe_arrays)
+                .unwrap_or(options.dedupe_arrays);
+            let order = rule
+                .and_then(|r| r.sort_arrays.as_ref())
+                .or(options.sort_arrays.as_ref());
+            let by = rule
+                .and_then(|r| r.sort_arrays_by.as_deref())
+                .or(options.sort_arrays_by.as_deref());
+            value.set_items(self.order_items::<T>(items, order, by, dedupe));
+            return;
+        }
+
+        let Some(mut members) = value.take_members() else {
+            return;
+        };
+
+        let order = rule
+            .and_then(|r| r.order.as_ref())
+            .unwrap_or(&options.order);
+        let first = rule
+            .and_then(|r| r.first.as_deref())
+            .unwrap_or(&options.first);
+        let last = rule
+            .and_then(|r| r.last.as_deref())
+            .unwrap_or(&options.last);
+
+        let order_by = rule
+            .and_then(|r| r.order_by.as_deref())
+            .or(options.order_by.as_deref());
+
+        self.order_members::<T>(&mut members, order, order_by, current_depth as usize + 1);
+
+        if !first.is_empty() || !last.is_empty() {
+            members.sort_by_key(|member| pin_rank(first, last, T::member_key(member)));
+        }
+
+        for member in members.iter_mut() {
+            if tracking {
+                self.path.push(T::member_key(member).to_string());
+            }
+            self.sort(T::member_value_mut(member), current_depth + 1);
+            if tracking {
+                self.path.pop();
+            }
+        }
+        value.set_members(members);
+    }
+}
+
+impl Sorter<'_> {
+    fn order_items<T: Sortable>(
+        &self,
+        items: Vec<T::Item>,
+        order: Option<&ArrayOrder>,
+        by: Option<&str>,
+        dedupe: bool,
+    ) -> Vec<T::Item> {
+        let mut items = items;
+        if dedupe {
+            let mut seen = HashSet::new();
+            items.retain(|item| seen.insert(T::item_value(item).dedupe_key()));
+        }
+
+        let Some(order) = order else {
+            return items;
+        };
+
+        let mut keyed: Vec<(Value, T::Item)> = items
+            .into_iter()
+            .map(|item| (T::item_value(&item).to_value().into_owned(), item))
+            .collect();
+
+        match order {
+            ArrayOrder::Asc => keyed.sort_by(|(a, _), (b, _)| {
+                compare_values(sort_value(a, by), sort_value(b, by), &self.collator)
+            }),
+            ArrayOrder::Desc => keyed.sort_by(|(a, _), (b, _)| {
+                compare_values(sort_value(b, by), sort_value(a, by), &self.collator)
+            }),
+        }
+
+        keyed.into_iter().map(|(_, item)| item).collect()
+    }
+
+    fn order_members<T: Sortable>(
+        &mut self,
+        members: &mut Vec<T::Member>,
+        order: &SortOrder,
+        by: Option<&str>,
+        level: usize,
+    ) {
+        if let SortOrder::Unsorted = order {
+            return;
+        }
+
+        let collator = &self.collator;
+
+        // Ties in the other orders fall back to alphabetical order.
+        members.sort_by(|a, b| collator.compare(T::member_key(a), T::member_key(b)));
+
+        match order {
+            SortOrder::AlphabeticalAsc | SortOrder::Unsorted => {}
+            SortOrder::AlphabeticalDesc => {
+                members.sort_by(|a, b| collator.compare(T::member_key(b), T::member_key(a)))
+            }
+            SortOrder::KeyLengthAsc => members.sort_by_key(|member| T::member_key(member).len()),
+            SortOrder::LineLength => {
+                let print = &self.options.print;
+                members.sort_by_cached_key(|member| {
+                    T::member_line(member, print, level).chars().count()
+                })
+            }
+            SortOrder::KeyLengthDesc => {
+                members.sort_by_key(|member| Reverse(T::member_key(member).len()))
+            }
+            SortOrder::NaturalAsc => {
+                members.sort_by(|a, b| natural_cmp(T::member_key(a), T::member_key(b), collator))
+            }
+            SortOrder::NaturalDesc => {
+                members.sort_by(|a, b| natural_cmp(T::member_key(b), T::member_key(a), collator))
+            }
+            SortOrder::ValueAsc | SortOrder::ValueDesc | SortOrder::ValueType => {
+                let mut keyed: Vec<(Value, T::Member)> = std::mem::take(members)
                     .into_iter()
-                    .map(|(k, v)| (k.clone(), sort(v, order, current_depth + 1, desired_depth)))
-                    .collect(),
-            )
+                    .map(|member| (T::member_value(&member).to_value().into_owned(), member))
+                    .collect();
+                keyed.sort_by(|(a, _), (b, _)| {
+                    let (a, b) = (sort_value(a, by), sort_value(b, by));
+                    match order {
+                        SortOrder::ValueDesc => compare_values(b, a, collator),
+                        SortOrder::ValueType => type_rank(a).cmp(&type_rank(b)),
+                        _ => compare_values(a, b, collator),
+                    }
+                });
+                *members = keyed.into_iter().map(|(_, member)| member).collect();
+            }
+            SortOrder::Random => members.shuffle(self.rng),
+        }
+    }
+}
+
+/// The part of `value` that value based orders compare: the member (or JSON
+/// Pointer) `by` of an object, otherwise the value itself.
+fn sort_value<'v>(value: &'v Value, by: Option<&str>) -> &'v Value {
+    static NULL: Value = Value::Null;
+    match (by, value) {
+        (Some(by), Value::Object(_)) => if by.starts_with('/') {
+            value.pointer(by)
+        } else {
+            value.get(by)
         }
-        Value::Array(arr) => Value::Array(
-            arr.iter()
-                .map(|v| sort(v, order, current_depth + 1, desired_depth))
-                .collect(),
-        ),
-        _ => value.clone(),
+        .unwrap_or(&NULL),
+        _ => value,
     }
 }
 
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Orders values by type (null, booleans, numbers, strings, arrays, objects)
+/// and then by content, comparing strings with `collator`.
+fn compare_values(a: &Value, b: &Value, collator: &KeyCollator) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => match (a.as_u64(), b.as_u64()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => a
+                    .as_f64()
+                    .partial_cmp(&b.as_f64())
+                    .unwrap_or(Ordering::Equal),
+            },
+        },
+        (Value::String(a), Value::String(b)) => collator.compare(a, b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare_values(a, b, collator))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        _ => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+/// Compares strings so that runs of digits are ordered by their numeric
+/// value, e.g. `a2` before `a10`. Other runs are compared with `collator`.
+fn natural_cmp(a: &str, b: &str, collator: &KeyCollator) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if is_digits(x) && is_digits(y) => {
+                let x = x.trim_start_matches('0');
+                let y = y.trim_start_matches('0');
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(x), Some(y)) => collator.compare(x, y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Splits a string into alternating runs of ASCII digits and other characters.
+fn chunks(value: &str) -> impl Iterator<Item = &str> {
+    let mut rest = value;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|ch: char| ch.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+fn is_digits(value: &str) -> bool {
+    value.starts_with(|ch: char| ch.is_ascii_digit())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,7 +631,7 @@ mod tests {
         }"#;
 
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_json = sort(&json, &SortOrder::AlphabeticalAsc, 0, None);
+        let sorted_json = sort(&json, &SortOptions::new(SortOrder::AlphabeticalAsc));
         assert_debug_snapshot!(sorted_json);
     }
 
@@ -81,7 +645,7 @@ mod tests {
         }"#;
 
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalAsc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::AlphabeticalAsc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -98,7 +662,7 @@ mod tests {
         }"#;
 
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalAsc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::AlphabeticalAsc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -115,7 +679,13 @@ mod tests {
         }"#;
 
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalAsc, 0, Some(1));
+        let sorted_obj = sort(
+            &json,
+            &SortOptions {
+                depth: Some(1),
+                ..SortOptions::new(SortOrder::AlphabeticalAsc)
+            },
+        );
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -128,7 +698,7 @@ mod tests {
             "a": 1
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalAsc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::AlphabeticalAsc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -141,93 +711,264 @@ mod tests {
             "c": 1
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalDesc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::AlphabeticalDesc));
         assert_debug_snapshot!(sorted_obj);
     }
 
-    // #[test]
-    // fn test_sort_json_overrides() {
-    //     let data = r#"
-    //     {
-    //         "e": 5,
-    //         "c": 3,
-    //         "f": 6,
-    //         "b": 2,
-    //         "d": 4,
-    //         "a": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort(
-    //         &obj,
-    //         &SortOrder::AlphabeticalAsc,
-    //         Some(vec!["c".to_string(), "d".to_string()]),
-    //         None,
-    //     );
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    fn pinned_options(order: SortOrder, first: &[&str], last: &[&str]) -> SortOptions {
+        SortOptions {
+            first: first.iter().map(|k| k.to_string()).collect(),
+            last: last.iter().map(|k| k.to_string()).collect(),
+            ..SortOptions::new(order)
+        }
+    }
 
-    // #[test]
-    // fn test_sort_json_reverse_overrides() {
-    //     let data = r#"
-    //     {
-    //         "e": 5,
-    //         "c": 3,
-    //         "f": 6,
-    //         "b": 2,
-    //         "d": 4,
-    //         "a": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort(
-    //         &obj,
-    //         &["desc"],
-    //         Some(vec!["c".to_string(), "d".to_string()]),
-    //         None,
-    //     );
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    #[test]
+    fn test_sort_json_overrides() {
+        let data = r#"
+        {
+            "e": 5,
+            "c": 3,
+            "f": 6,
+            "b": 2,
+            "d": 4,
+            "a": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(
+            &json,
+            &pinned_options(SortOrder::AlphabeticalAsc, &["c", "d"], &[]),
+        );
+        assert_debug_snapshot!(sorted_obj);
+    }
 
-    // #[test]
-    // fn test_sort_json_underrides() {
-    //     let data = r#"
-    //     {
-    //         "e": 5,
-    //         "c": 3,
-    //         "f": 6,
-    //         "b": 2,
-    //         "d": 4,
-    //         "a": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort(
-    //         &obj,
-    //         SortOrder::AlphabeticalAsc,
-    //         None,
-    //         Some(vec!["c".to_string(), "d".to_string()]),
-    //     );
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    #[test]
+    fn test_sort_json_reverse_overrides() {
+        let data = r#"
+        {
+            "e": 5,
+            "c": 3,
+            "f": 6,
+            "b": 2,
+            "d": 4,
+            "a": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(
+            &json,
+            &pinned_options(SortOrder::AlphabeticalDesc, &["c", "d"], &[]),
+        );
+        assert_debug_snapshot!(sorted_obj);
+    }
 
-    // #[test]
-    // fn test_sort_json_reverse_underrides() {
-    //     let data = r#"
-    //     {
-    //         "e": 5,
-    //         "c": 3,
-    //         "f": 6,
-    //         "b": 2,
-    //         "d": 4,
-    //         "a": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort(
-    //         &obj,
-    //         &["desc"],
-    //         None,
-    //         Some(vec!["c".to_string(), "d".to_string()]),
-    //     );
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    #[test]
+    fn test_sort_json_underrides() {
+        let data = r#"
+        {
+            "e": 5,
+            "c": 3,
+            "f": 6,
+            "b": 2,
+            "d": 4,
+            "a": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(
+            &json,
+            &pinned_options(SortOrder::AlphabeticalAsc, &[], &["c", "d"]),
+        );
+        assert_debug_snapshot!(sorted_obj);
+    }
+
+    #[test]
+    fn test_sort_json_reverse_underrides() {
+        let data = r#"
+        {
+            "e": 5,
+            "c": 3,
+            "f": 6,
+            "b": 2,
+            "d": 4,
+            "a": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(
+            &json,
+            &pinned_options(SortOrder::AlphabeticalDesc, &[], &["c", "d"]),
+        );
+        assert_debug_snapshot!(sorted_obj);
+    }
+
+    #[test]
+    fn test_sort_json_pinned_applies_to_nested_objects() {
+        let data = r#"
+        {
+            "scripts": { "test": 1, "name": 2 },
+            "author": 3,
+            "name": 4
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(
+            &json,
+            &pinned_options(SortOrder::AlphabeticalAsc, &["name"], &["scripts"]),
+        );
+        assert_eq!(
+            serde_json::to_string(&sorted_obj).unwrap(),
+            r#"{"name":4,"author":3,"scripts":{"name":2,"test":1}}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_json_rules_apply_per_path() {
+        let data = r#"
+        {
+            "scripts": { "test": 1, "build": 2 },
+            "dependencies": { "b": 1, "a": 2 },
+            "compilerOptions": { "paths": { "a": [], "c": [] }, "strict": true, "baseUrl": "." },
+            "items": [{ "y": 1, "x": 2 }]
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let rule = |path: &str, order: SortOrder| SortRule {
+            path: path.parse().unwrap(),
+            order: Some(order),
+            ..SortRule::default()
+        };
+        let options = SortOptions {
+            rules: vec![
+                rule("", SortOrder::Unsorted),
+                rule("/scripts", SortOrder::Unsorted),
+                rule("/compilerOptions/paths", SortOrder::AlphabeticalDesc),
+                rule("/items/*", SortOrder::AlphabeticalDesc),
+            ],
+            ..SortOptions::new(SortOrder::AlphabeticalAsc)
+        };
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"scripts":{"test":1,"build":2},"dependencies":{"a":2,"b":1},"compilerOptions":{"baseUrl":".","paths":{"c":[],"a":[]},"strict":true},"items":[{"y":1,"x":2}]}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_json_arrays_by_value() {
+        let data = r#"
+        {
+            "keywords": ["json", "cli", "sort", "cli", 10, 9, null, true],
+            "nested": [[2], [1, 5], [1]]
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let options = SortOptions {
+            sort_arrays: Some(ArrayOrder::Asc),
+            dedupe_arrays: true,
+            ..SortOptions::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"keywords":[null,true,9,10,"cli","json","sort"],"nested":[[1],[1,5],[2]]}"#
+        );
+    }
+
+    #[test]
+    fn test_number_key() {
+        for same in [
+            ["1", "1.0", "1e0"],
+            ["-120", "-1.2e2", "-12E+1"],
+            ["0", "-0.0", "0e5"],
+        ] {
+            assert!(same
+                .iter()
+                .all(|raw| number_key(raw) == number_key(same[0])));
+        }
+        assert_eq!(number_key("0x1F"), number_key("31"));
+        assert_eq!(number_key("+Infinity"), number_key("Infinity"));
+        assert_ne!(number_key("-Infinity"), number_key("Infinity"));
+        assert_ne!(
+            number_key("18446744073709551616"),
+            number_key("18446744073709551617")
+        );
+        assert_ne!(number_key("0.1"), number_key("1"));
+    }
+
+    #[test]
+    fn test_sort_json_arrays_of_objects_by_key_per_path() {
+        let data = r#"
+        {
+            "permissions": [{ "name": "write", "id": 1 }, { "name": "admin", "id": 3 }, { "id": 2 }],
+            "files": ["b", "a"]
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let options = SortOptions {
+            rules: vec![SortRule {
+                path: "/permissions".parse().unwrap(),
+                sort_arrays: Some(ArrayOrder::Desc),
+                sort_arrays_by: Some("name".to_string()),
+                ..SortRule::default()
+            }],
+            ..SortOptions::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &options)).unwrap(),
+            r#"{"files":["b","a"],"permissions":[{"id":1,"name":"write"},{"id":3,"name":"admin"},{"id":2}]}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_json_random_with_seed_is_reproducible() {
+        let data =
+            r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": {"x": 1, "y": 2, "z": 3}}"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let seeded = |seed| {
+            let options = SortOptions {
+                seed: Some(seed),
+                ..SortOptions::new(SortOrder::Random)
+            };
+            serde_json::to_string(&sort(&json, &options)).unwrap()
+        };
+        assert_eq!(seeded(42), seeded(42));
+        assert_ne!(seeded(42), seeded(7));
+    }
+
+    #[test]
+    fn test_sort_json_by_value() {
+        let data = r#"{"search": 3, "export": 1, "beta": null, "dark-mode": 2, "import": 1}"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &SortOptions::new(SortOrder::ValueAsc))).unwrap(),
+            r#"{"beta":null,"export":1,"import":1,"dark-mode":2,"search":3}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &SortOptions::new(SortOrder::ValueDesc))).unwrap(),
+            r#"{"search":3,"dark-mode":2,"export":1,"import":1,"beta":null}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_json_by_value_type() {
+        let data = r#"{"o": {}, "s": "x", "n": 1, "a": [], "b": false, "z": null, "m": 0}"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &SortOptions::new(SortOrder::ValueType))).unwrap(),
+            r#"{"z":null,"b":false,"m":0,"n":1,"s":"x","a":[],"o":{}}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_json_by_nested_field() {
+        let data = r#"
+        {
+            "low": { "meta": { "priority": 3 } },
+            "high": { "meta": { "priority": 1 } },
+            "mid": { "meta": { "priority": 2 } }
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let options = SortOptions {
+            order_by: Some("/meta/priority".to_string()),
+            ..SortOptions::new(SortOrder::ValueAsc)
+        };
+        let Value::Object(map) = sort(&json, &options) else {
+            panic!("expected object");
+        };
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["high", "mid", "low"]);
+    }
 
     #[test]
     fn test_sort_json_array_of_objects() {
@@ -247,7 +988,7 @@ mod tests {
             "a": 1
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::AlphabeticalAsc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::AlphabeticalAsc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -260,7 +1001,7 @@ mod tests {
             "a": 1
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::KeyLengthAsc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::KeyLengthAsc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -273,7 +1014,7 @@ mod tests {
             "bbb": 3
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::KeyLengthDesc, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::KeyLengthDesc));
         assert_debug_snapshot!(sorted_obj);
     }
 
@@ -287,10 +1028,20 @@ mod tests {
             "cat": 4
         }"#;
         let json: Value = serde_json::from_str(data).unwrap();
-        let sorted_obj = sort(&json, &SortOrder::LineLength, 0, None);
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::LineLength));
         assert_debug_snapshot!(sorted_obj);
     }
 
+    #[test]
+    fn test_sort_json_line_length_counts_values() {
+        let data = r#"{"a": "a long string value", "bbb": 1, "d": [], "cc": {"x": 1}}"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            serde_json::to_string(&sort(&json, &SortOptions::new(SortOrder::LineLength))).unwrap(),
+            r#"{"cc":{"x":1},"d":[],"bbb":1,"a":"a long string value"}"#
+        );
+    }
+
     // #[test]
     // fn test_sort_json_key_length() {
     //     let data = r#"
@@ -317,35 +1068,104 @@ mod tests {
     //     assert_debug_snapshot!(sorted_obj);
     // }
 
-    // #[test]
-    // fn test_sort_json_alphanum() {
-    //     let data = r#"
-    //     {
-    //         "a10": 3,
-    //         "a2": 2,
-    //         "a1": 1,
-    //         "b11": 3,
-    //         "b2": 2,
-    //         "b0": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort_json_alphanum(&json, &SortOrder::AlphabeticalAsc);
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    #[test]
+    fn test_sort_json_alphanum() {
+        let data = r#"
+        {
+            "a10": 3,
+            "a2": 2,
+            "a1": 1,
+            "b11": 3,
+            "b2": 2,
+            "b0": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::NaturalAsc));
+        assert_debug_snapshot!(sorted_obj);
+    }
 
-    // #[test]
-    // fn test_sort_json_reverse_alphanum() {
-    //     let data = r#"
-    //     {
-    //         "a10": 3,
-    //         "a2": 2,
-    //         "a1": 1,
-    //         "b11": 3,
-    //         "b2": 2,
-    //         "b0": 1
-    //     }"#;
-    //             let json: Value = serde_json::from_str(data).unwrap();
-    //     let sorted_obj = sort_json_alphanum(&json, &SortOrder::AlphabeticalDesc);
-    //     assert_debug_snapshot!(sorted_obj);
-    // }
+    #[test]
+    fn test_sort_json_reverse_alphanum() {
+        let data = r#"
+        {
+            "a10": 3,
+            "a2": 2,
+            "a1": 1,
+            "b11": 3,
+            "b2": 2,
+            "b0": 1
+        }"#;
+        let json: Value = serde_json::from_str(data).unwrap();
+        let sorted_obj = sort(&json, &SortOptions::new(SortOrder::NaturalDesc));
+        assert_debug_snapshot!(sorted_obj);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let binary = KeyCollator::Binary;
+        assert_eq!(natural_cmp("item2", "item10", &binary), Ordering::Less);
+        assert_eq!(natural_cmp("v1.10.0", "v1.9.2", &binary), Ordering::Greater);
+        assert_eq!(natural_cmp("port008", "port8", &binary), Ordering::Less);
+        assert_eq!(natural_cmp("a", "a1", &binary), Ordering::Less);
+        assert_eq!(natural_cmp("b1", "a2", &binary), Ordering::Greater);
+        assert_eq!(
+            natural_cmp("Item2", "item10", &KeyCollator::CaseInsensitive),
+            Ordering::Less
+        );
+    }
+
+    fn collated_keys(collation: Collation, locale: Option<&str>) -> Vec<String> {
+        let json: Value = serde_json::from_str(
+            r#"{"zebra": 1, "Zebra": 2, "apple": 3, "Apple": 4, "éclair": 5}"#,
+        )
+        .unwrap();
+        let options = SortOptions {
+            collation,
+            locale: locale.map(|locale| locale.parse().unwrap()),
+            ..SortOptions::default()
+        };
+        let Value::Object(map) = sort(&json, &options) else {
+            panic!("expected object");
+        };
+        map.keys().cloned().collect()
+    }
+
+    #[test]
+    fn test_sort_json_collation() {
+        assert_eq!(
+            collated_keys(Collation::Binary, None),
+            ["Apple", "Zebra", "apple", "zebra", "\u{e9}clair"]
+        );
+        assert_eq!(
+            collated_keys(Collation::CaseInsensitive, None),
+            ["Apple", "apple", "Zebra", "zebra", "\u{e9}clair"]
+        );
+        assert_eq!(
+            collated_keys(Collation::LowerFirst, None),
+            ["apple", "Apple", "zebra", "Zebra", "\u{e9}clair"]
+        );
+        assert_eq!(
+            collated_keys(Collation::UpperFirst, None),
+            ["Apple", "apple", "Zebra", "zebra", "\u{e9}clair"]
+        );
+        assert_eq!(
+            collated_keys(Collation::Utf16, None),
+            ["Apple", "Zebra", "apple", "zebra", "\u{e9}clair"]
+        );
+        assert_eq!(
+            collated_keys(Collation::Unicode, Some("en")),
+            ["apple", "Apple", "\u{e9}clair", "zebra", "Zebra"]
+        );
+    }
+
+    #[test]
+    fn test_check_locale_rejects_unknown_languages() {
+        for locale in ["de", "sv-SE", "zh-u-co-pinyin", "und"] {
+            assert_eq!(check_locale(&locale.parse().unwrap()), Ok(()), "{locale}");
+        }
+        assert_eq!(
+            check_locale(&"dee".parse().unwrap()),
+            Err("unknown language `dee`".to_string())
+        );
+    }
 }
@@ -0,0 +1,881 @@
+use rand::rngs::Xoshiro256PlusPlus;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::cst::{self, DuplicateKey, Key, Member, Node, Object, PrintOptions, Scalar};
+use crate::files::Extension;
+use crate::format::{self, Error, FormatOptions, Result};
+use crate::sort::{self, SortOptions};
+use crate::{DuplicateKeys, SortOrder, Style, TrailingCommas};
+
+/// How often progress is reported, in bytes read.
+const PROGRESS_STEP: u64 = 1 << 20;
+/// How much of the file is read up front to detect its indent and line breaks.
+const SAMPLE_SIZE: u64 = 64 * 1024;
+
+/// The result of streaming one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamOutcome {
+    /// Whether the output differs from the file.
+    pub changed: bool,
+    /// Repeated keys in the parts of the file that were sorted.
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+/// Formats the file at `path` like [`format::format_file`], but holds only a
+/// bounded part of it in memory and writes the result to `output`.
+///
+/// An array at the root is read one element at a time, each one sorted on its
+/// own. An object at the root can only be sorted with a depth of 1: its keys
+/// are read first with the position of their values, then sorted, and each
+/// value is copied from the file in the new order. `.jsonl` files are read a
+/// line at a time. Comments, JSON5 and the settings that need the whole
+/// document (print width, canonical output, value orders and sorting the root
+/// array) are rejected.
+///
+/// `progress` is called with the number of bytes read so far and the number
+/// expected in total, about once per MiB.
+pub fn format_stream(
+    path: &Path,
+    output: impl Write,
+    options: &FormatOptions,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<StreamOutcome> {
+    let options = options.with_editorconfig(Some(path))?;
+    let extension = Extension::of(path);
+    if options.canonical.is_some() {
+        return Err(Error::Stream("canonical output".to_string()));
+    }
+    if options.print_width.is_some() {
+        return Err(Error::Stream("a print width".to_string()));
+    }
+    if extension == Some(Extension::Json5) {
+        return Err(Error::Stream("JSON5 files".to_string()));
+    }
+
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let mut sample = Vec::new();
+    (&mut file).take(SAMPLE_SIZE).read_to_end(&mut sample)?;
+    let sample = String::from_utf8_lossy(&sample);
+    let mut last = [0];
+    if len > 0 {
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+    }
+    file.seek(SeekFrom::Start(0))?;
+
+    let final_newline = options
+        .insert_final_newline
+        .unwrap_or(matches!(last[0], b'\n' | b'\r'));
+    let end_of_line = match &options.end_of_line {
+        Some(end_of_line) => end_of_line.clone(),
+        None => format::detect_end_of_line(&sample),
+    };
+    let print = options.print_options(&sample, extension.as_ref());
+    let sort = options.sort_options(Some(path), &print);
+
+    let mut streamer = Streamer {
+        reader: Reader {
+            input: BufReader::new(file),
+            position: Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            read: 0,
+            total: len,
+            next_report: PROGRESS_STEP,
+            progress: &mut progress,
+            capture: None,
+        },
+        out: Output {
+            inner: output,
+            original: BufReader::new(File::open(path)?),
+            changed: false,
+            end_of_line: end_of_line.as_str(),
+        },
+        print: &print,
+        sort: &sort,
+        duplicate_keys: &options.duplicate_keys,
+        duplicates: Vec::new(),
+        rng: sort::random_source(&sort),
+    };
+
+    match &extension {
+        Some(extension) if extension.is_lines() => streamer.lines(&options)?,
+        _ => streamer.document()?,
+    }
+    if final_newline {
+        streamer.out.write("\n")?;
+    }
+
+    Ok(StreamOutcome {
+        changed: streamer.out.finish()?,
+        duplicates: streamer.duplicates,
+    })
+}
+
+/// Where a token starts, to seek back to it or report an error there.
+#[derive(Debug, Clone)]
+struct Position {
+    offset: u64,
+    line: usize,
+    column: usize,
+}
+
+/// Reads the file a byte at a time, tracking the position and progress.
+struct Reader<'p, R> {
+    input: R,
+    position: Position,
+    /// Bytes read so far, across every pass over the file.
+    read: u64,
+    total: u64,
+    next_report: u64,
+    progress: &'p mut dyn FnMut(u64, u64),
+    /// Receives every byte read while set, e.g. to collect one record.
+    capture: Option<Vec<u8>>,
+}
+
+impl<R: BufRead + Seek> Reader<'_, R> {
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.input.fill_buf()?.first().copied())
+    }
+
+    fn bump(&mut self) -> Result<Option<u8>> {
+        let Some(byte) = self.peek()? else {
+            return Ok(None);
+        };
+        self.input.consume(1);
+        self.advance(&[byte]);
+        Ok(Some(byte))
+    }
+
+    fn advance(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if byte == b'\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                // Columns count characters, not UTF-8 continuation bytes.
+                self.position.column += 1;
+            }
+        }
+        self.position.offset += bytes.len() as u64;
+        self.read += bytes.len() as u64;
+        if let Some(capture) = &mut self.capture {
+            capture.extend_from_slice(bytes);
+        }
+        if self.read >= self.next_report {
+            (self.progress)(self.read.min(self.total), self.total);
+            self.next_report = self.read + PROGRESS_STEP;
+        }
+    }
+
+    fn seek(&mut self, position: &Position) -> Result<()> {
+        self.input.seek(SeekFrom::Start(position.offset))?;
+        self.position = position.clone();
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> Error {
+        error_at(&self.position, message)
+    }
+
+    fn skip_bom(&mut self) -> Result<()> {
+        if self.input.fill_buf()?.starts_with("\u{feff}".as_bytes()) {
+            self.input.consume(3);
+            self.position.offset += 3;
+            self.read += 3;
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        loop {
+            match self.peek()? {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => {
+                    self.bump()?;
+                }
+                Some(b'/') => return Err(Error::Stream("comments".to_string())),
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<()> {
+        if self.peek()? == Some(expected) {
+            self.bump()?;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", expected as char)))
+        }
+    }
+
+    /// Appends a double-quoted string token to `raw`.
+    fn string(&mut self, raw: &mut Vec<u8>) -> Result<()> {
+        let start = self.position.clone();
+        raw.extend(self.bump()?);
+        loop {
+            match self.bump()? {
+                None => return Err(error_at(&start, "unterminated string")),
+                Some(b'\\') => {
+                    raw.push(b'\\');
+                    raw.extend(self.bump()?);
+                }
+                Some(b'"') => {
+                    raw.push(b'"');
+                    return Ok(());
+                }
+                Some(byte) => raw.push(byte),
+            }
+        }
+    }
+
+    /// Reads a string token and the text it decodes to.
+    fn string_value(&mut self) -> Result<(String, String)> {
+        let start = self.position.clone();
+        let mut raw = Vec::new();
+        self.string(&mut raw)?;
+        let raw = String::from_utf8(raw).map_err(|_| error_at(&start, "invalid UTF-8"))?;
+        let value =
+            serde_json::from_str::<String>(&raw).map_err(|_| error_at(&start, "invalid string"))?;
+        Ok((raw, value))
+    }
+
+    /// Reads a number or a literal such as `true`.
+    fn word(&mut self) -> Result<String> {
+        let mut raw = String::new();
+        while let Some(byte) = self.peek()? {
+            if !byte.is_ascii_alphanumeric() && !matches!(byte, b'+' | b'-' | b'.') {
+                break;
+            }
+            raw.push(byte as char);
+            self.bump()?;
+        }
+        if raw.is_empty() {
+            return Err(self.error("expected value"));
+        }
+        Ok(raw)
+    }
+
+    /// Reads one value without interpreting it beyond matching its brackets.
+    fn skip_value(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        let mut scratch = Vec::new();
+        loop {
+            match self.peek()? {
+                None => return Err(self.error("unexpected end of input")),
+                Some(b'"') => {
+                    scratch.clear();
+                    self.string(&mut scratch)?;
+                }
+                Some(b'{' | b'[') => {
+                    self.bump()?;
+                    depth += 1;
+                    continue;
+                }
+                Some(b'}' | b']') if depth > 0 => {
+                    self.bump()?;
+                    depth -= 1;
+                }
+                Some(b'/') => return Err(Error::Stream("comments".to_string())),
+                Some(b' ' | b'\t' | b'\n' | b'\r' | b',' | b':') if depth > 0 => {
+                    self.bump()?;
+                    continue;
+                }
+                Some(_) => {
+                    self.word()?;
+                }
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn error_at(position: &Position, message: &str) -> Error {
+    Error::Parse(cst::Error {
+        message: message.to_string(),
+        line: position.line,
+        column: position.column,
+    })
+}
+
+/// Writes the formatted document with the chosen line breaks, comparing it
+/// with the original file along the way.
+struct Output<W> {
+    inner: W,
+    original: BufReader<File>,
+    changed: bool,
+    end_of_line: &'static str,
+}
+
+impl<W: Write> Output<W> {
+    fn write(&mut self, text: &str) -> Result<()> {
+        if self.end_of_line != "\n" && text.contains('\n') {
+            self.emit(text.replace('\n', self.end_of_line).as_bytes())
+        } else {
+            self.emit(text.as_bytes())
+        }
+    }
+
+    fn emit(&mut self, mut bytes: &[u8]) -> Result<()> {
+        self.inner.write_all(bytes)?;
+        while !self.changed && !bytes.is_empty() {
+            let original = self.original.fill_buf()?;
+            let len = original.len().min(bytes.len());
+            self.changed = len == 0 || original[..len] != bytes[..len];
+            self.original.consume(len);
+            bytes = &bytes[len..];
+        }
+        Ok(())
+    }
+
+    /// Flushes the output and reports whether it differs from the original.
+    fn finish(mut self) -> Result<bool> {
+        self.inner.flush()?;
+        Ok(self.changed || !self.original.fill_buf()?.is_empty())
+    }
+}
+
+struct Streamer<'a, 'p, W> {
+    reader: Reader<'p, BufReader<File>>,
+    out: Output<W>,
+    print: &'a PrintOptions,
+    sort: &'a SortOptions,
+    duplicate_keys: &'a DuplicateKeys,
+    duplicates: Vec<DuplicateKey>,
+    /// Shared by every record so a seeded random order matches [`sort::sort`].
+    rng: Xoshiro256PlusPlus,
+}
+
+impl<W: Write> Streamer<'_, '_, W> {
+    fn document(&mut self) -> Result<()> {
+        self.reader.skip_bom()?;
+        self.reader.skip_whitespace()?;
+        match (self.reader.peek()?, self.sort.depth) {
+            (_, Some(0)) => self.copy_value(0)?,
+            (Some(b'['), _) => self.records()?,
+            (Some(b'{'), Some(1)) => self.members()?,
+            (Some(b'{'), _) => {
+                return Err(Error::Stream(
+                    "an object at the root unless the sort depth is 1".to_string(),
+                ))
+            }
+            _ => self.copy_value(0)?,
+        }
+        self.reader.skip_whitespace()?;
+        if self.reader.peek()?.is_some() {
+            return Err(self.reader.error("trailing characters"));
+        }
+        Ok(())
+    }
+
+    /// Formats a JSON Lines file one line at a time.
+    fn lines(&mut self, options: &FormatOptions) -> Result<()> {
+        let mut line = String::new();
+        let mut number = 0;
+        loop {
+            line.clear();
+            let read = self.reader.input.read_line(&mut line)?;
+            if read == 0 {
+                return Ok(());
+            }
+            self.reader.advance(line.as_bytes());
+            number += 1;
+            if number > 1 {
+                self.out.write("\n")?;
+            }
+            let record = line.trim_end_matches(['\n', '\r']);
+            if record.trim().is_empty() {
+                continue;
+            }
+            let outcome = format::format_line(record, number, options)?;
+            self.out.write(&outcome.output)?;
+            self.duplicates.extend(outcome.duplicates);
+        }
+    }
+
+    /// Writes an array at the root one element at a time, sorting each one
+    /// unless the sort depth stops at the array.
+    fn records(&mut self) -> Result<()> {
+        let reorders_root = self.sort.sort_arrays.is_some()
+            || self.sort.dedupe_arrays
+            || self.sort.rules.iter().any(|rule| {
+                rule.path.matches::<&str>(&[])
+                    && (rule.sort_arrays.is_some() || rule.dedupe_arrays == Some(true))
+            });
+        if reorders_root {
+            return Err(Error::Stream(
+                "sorting or deduplicating the array at the root".to_string(),
+            ));
+        }
+
+        let sorted = self.sort.depth != Some(1);
+        self.reader.bump()?;
+        self.out.write("[")?;
+        self.elements(0, b']', |streamer, index| {
+            if sorted {
+                streamer.record(index)
+            } else {
+                streamer.copy_value(1)
+            }
+        })
+    }
+
+    /// Reads one element of the root array and writes it sorted.
+    fn record(&mut self, index: usize) -> Result<()> {
+        let start = self.reader.position.clone();
+        self.reader.capture = Some(Vec::new());
+        let scanned = self.reader.skip_value();
+        let raw = self.reader.capture.take().unwrap_or_default();
+        scanned?;
+
+        // Positions within the record are made relative to the file.
+        let line = |line: usize| line + start.line - 1;
+        let column = |line: usize, column: usize| match line {
+            1 => column + start.column - 1,
+            _ => column,
+        };
+        let relocate = |err: cst::Error| {
+            Error::Parse(cst::Error {
+                line: line(err.line),
+                column: column(err.line, err.column),
+                ..err
+            })
+        };
+
+        let relocate_duplicate = |duplicate: DuplicateKey| DuplicateKey {
+            line: line(duplicate.line),
+            column: column(duplicate.line, duplicate.column),
+            ..duplicate
+        };
+
+        let text = String::from_utf8(raw).map_err(|_| error_at(&start, "invalid UTF-8"))?;
+        let mut document = cst::parse(&text).map_err(relocate)?;
+        document.resolve_duplicates(self.duplicate_keys).map_err(
+            |cst::DuplicateKeysError(duplicates)| {
+                cst::DuplicateKeysError(duplicates.into_iter().map(relocate_duplicate).collect())
+            },
+        )?;
+        self.duplicates
+            .extend(document.duplicates.drain(..).map(relocate_duplicate));
+
+        document.root = sort::sort_at(
+            &document.root,
+            self.sort,
+            &[index.to_string()],
+            &mut self.rng,
+        );
+        let printed = cst::print(&document, self.print);
+        self.out
+            .write(&printed.replace('\n', &format!("\n{}", self.print.indent)))
+    }
+
+    /// Sorts the keys of an object at the root without holding its values:
+    /// the keys are read with the position of each value, sorted, and every
+    /// value is then copied from its position.
+    fn members(&mut self) -> Result<()> {
+        let orders = std::iter::once(&self.sort.order).chain(
+            self.sort
+                .rules
+                .iter()
+                .filter_map(|rule| rule.order.as_ref()),
+        );
+        for order in orders {
+            if matches!(
+                order,
+                SortOrder::ValueAsc
+                    | SortOrder::ValueDesc
+                    | SortOrder::ValueType
+                    | SortOrder::LineLength
+            ) {
+                return Err(Error::Stream(
+                    "ordering keys by their values or lines".to_string(),
+                ));
+            }
+        }
+        // The values are read a second time to copy them.
+        self.reader.total = self.reader.total.saturating_mul(2);
+
+        let mut members = Vec::new();
+        let mut positions = Vec::new();
+        let mut names = HashSet::new();
+        let mut duplicates = Vec::new();
+        let mut trailing_comma = false;
+
+        self.reader.bump()?;
+        self.reader.skip_whitespace()?;
+        while self.reader.peek()? != Some(b'}') {
+            if self.reader.peek()? != Some(b'"') {
+                return Err(self.reader.error("expected key"));
+            }
+            let start = self.reader.position.clone();
+            let (raw, name) = self.reader.string_value()?;
+            if !names.insert(name.clone()) {
+                duplicates.push(DuplicateKey {
+                    name: name.clone(),
+                    line: start.line,
+                    column: start.column,
+                });
+            }
+            self.reader.skip_whitespace()?;
+            self.reader.expect(b':')?;
+            self.reader.skip_whitespace()?;
+
+            members.push(Member {
+                leading: Vec::new(),
+                key: Key { raw, name },
+                // Stands in for the value, which is copied later.
+                value: Node::Scalar(Scalar {
+                    raw: positions.len().to_string(),
+                    value: Value::from(positions.len()),
+                }),
+                trailing: Vec::new(),
+            });
+            positions.push(self.reader.position.clone());
+            self.reader.skip_value()?;
+
+            self.reader.skip_whitespace()?;
+            trailing_comma = false;
+            match self.reader.peek()? {
+                Some(b',') => {
+                    self.reader.bump()?;
+                    self.reader.skip_whitespace()?;
+                    trailing_comma = true;
+                }
+                Some(b'}') => {}
+                _ => return Err(self.reader.error("expected `,` or `}`")),
+            }
+        }
+        self.reader.bump()?;
+        let end = self.reader.position.clone();
+
+        let mut document = cst::Document {
+            leading: Vec::new(),
+            root: Node::Object(Object {
+                members,
+                dangling: Vec::new(),
+                trailing_comma,
+            }),
+            trailing: Vec::new(),
+            duplicates,
+        };
+        document.resolve_duplicates(self.duplicate_keys)?;
+        self.duplicates.append(&mut document.duplicates);
+        let Node::Object(object) = sort::sort(&document.root, self.sort) else {
+            unreachable!("sorting keeps the object");
+        };
+
+        self.out.write("{")?;
+        for (index, member) in object.members.iter().enumerate() {
+            if index > 0 {
+                self.out.write(",")?;
+            }
+            self.newline(1)?;
+            self.key(&member.key.raw, &member.key.name)?;
+            if let Node::Scalar(Scalar { value, .. }) = &member.value {
+                let position = value.as_u64().unwrap_or_default() as usize;
+                self.reader.seek(&positions[position])?;
+            }
+            self.copy_value(1)?;
+        }
+        if !object.members.is_empty() {
+            if self.trailing_comma(object.trailing_comma) {
+                self.out.write(",")?;
+            }
+            self.newline(0)?;
+        }
+        self.out.write("}")?;
+        self.reader.seek(&end)
+    }
+
+    /// Reads the elements of a container up to `close`, writing the commas and
+    /// line breaks around each one as the printer would.
+    fn elements(
+        &mut self,
+        level: usize,
+        close: u8,
+        mut element: impl FnMut(&mut Self, usize) -> Result<()>,
+    ) -> Result<()> {
+        self.reader.skip_whitespace()?;
+        let mut count = 0;
+        let mut trailing = false;
+        while self.reader.peek()? != Some(close) {
+            if count > 0 {
+                self.out.write(",")?;
+            }
+            self.newline(level + 1)?;
+            element(self, count)?;
+            count += 1;
+
+            self.reader.skip_whitespace()?;
+            trailing = false;
+            match self.reader.peek()? {
+                Some(b',') => {
+                    self.reader.bump()?;
+                    self.reader.skip_whitespace()?;
+                    trailing = true;
+                }
+                Some(byte) if byte == close => {}
+                _ => {
+                    return Err(self
+                        .reader
+                        .error(&format!("expected `,` or `{}`", close as char)))
+                }
+            }
+        }
+        self.reader.bump()?;
+
+        if count > 0 {
+            if self.trailing_comma(trailing) {
+                self.out.write(",")?;
+            }
+            self.newline(level)?;
+        }
+        self.out.write(if close == b'}' { "}" } else { "]" })
+    }
+
+    /// Copies one value, laid out as the printer would but never reordered.
+    fn copy_value(&mut self, level: usize) -> Result<()> {
+        match self.reader.peek()? {
+            Some(b'{' | b'[') if level == cst::MAX_DEPTH => {
+                Err(self.reader.error("nesting too deep"))
+            }
+            Some(b'{') => {
+                self.reader.bump()?;
+                self.out.write("{")?;
+                self.elements(level, b'}', |streamer, _| streamer.copy_member(level + 1))
+            }
+            Some(b'[') => {
+                self.reader.bump()?;
+                self.out.write("[")?;
+                self.elements(level, b']', |streamer, _| streamer.copy_value(level + 1))
+            }
+            Some(b'"') => {
+                let (raw, value) = self.reader.string_value()?;
+                self.string(&raw, &value)
+            }
+            _ => {
+                let start = self.reader.position.clone();
+                let raw = self.reader.word()?;
+                if !matches!(raw.as_str(), "true" | "false" | "null") && !cst::is_json_number(&raw)
+                {
+                    return Err(error_at(&start, "invalid number"));
+                }
+                self.out.write(&raw)
+            }
+        }
+    }
+
+    fn copy_member(&mut self, level: usize) -> Result<()> {
+        if self.reader.peek()? != Some(b'"') {
+            return Err(self.reader.error("expected key"));
+        }
+        let (raw, name) = self.reader.string_value()?;
+        self.key(&raw, &name)?;
+        self.reader.skip_whitespace()?;
+        self.reader.expect(b':')?;
+        self.reader.skip_whitespace()?;
+        self.copy_value(level)
+    }
+
+    fn key(&mut self, raw: &str, name: &str) -> Result<()> {
+        self.string(raw, name)?;
+        self.out.write(match self.print.style {
+            Style::Compact => ":",
+            Style::Pretty => ": ",
+        })
+    }
+
+    fn string(&mut self, raw: &str, value: &str) -> Result<()> {
+        let mut text = String::new();
+        cst::write_string(&mut text, raw, value, self.print);
+        self.out.write(&text)
+    }
+
+    fn newline(&mut self, level: usize) -> Result<()> {
+        if self.print.style == Style::Compact {
+            return Ok(());
+        }
+        self.out.write("\n")?;
+        for _ in 0..level {
+            self.out.write(&self.print.indent)?;
+        }
+        Ok(())
+    }
+
+    fn trailing_comma(&self, had: bool) -> bool {
+        match (&self.print.style, &self.print.trailing_commas) {
+            (Style::Compact, _) | (_, TrailingCommas::Never) => false,
+            (_, TrailingCommas::Always) => true,
+            (_, TrailingCommas::Preserve) => had,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::format_file;
+    use crate::ArrayOrder;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn stream(path: &Path, options: &FormatOptions) -> Result<(String, StreamOutcome)> {
+        let mut output = Vec::new();
+        let outcome = format_stream(path, &mut output, options, |_, _| {})?;
+        Ok((String::from_utf8(output).unwrap(), outcome))
+    }
+
+    #[test]
+    fn test_stream_matches_format_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let inputs = [
+            "[\r\n  {\"b\": {\"d\": 1, \"c\": [true, null]}, \"a\": \"\\u00e9\"},\r\n  {},\r\n  []\r\n]",
+            "[{\"z\": 1e3, \"y\": -0.5}, [{\"b\": 1, \"a\": 2}]]\n",
+            "\u{feff}  \"text\"  \n",
+            "[]",
+            "[{\"a\": 1, \"b\": 2, \"c\": 3, \"d\": 4}, {\"a\": 1, \"b\": 2, \"c\": 3, \"d\": 4}, {\"a\": 1, \"b\": 2, \"c\": 3, \"d\": 4}]",
+        ];
+        let options = [
+            FormatOptions::new(),
+            FormatOptions::new()
+                .indent(4)
+                .trailing_commas(TrailingCommas::Always),
+            FormatOptions::new().style(Style::Compact),
+            FormatOptions::new().sort(SortOptions {
+                seed: Some(7),
+                ..SortOptions::new(SortOrder::Random)
+            }),
+        ];
+        for name in ["records.json", "records.jsonc"] {
+            let path = temp_dir.path().join(name);
+            for input in inputs {
+                fs::write(&path, input).unwrap();
+                for options in &options {
+                    let expected = format_file(&path, options).unwrap();
+                    let (output, outcome) = stream(&path, options).unwrap();
+                    assert_eq!(output, expected.output, "{name}: {input:?}");
+                    assert_eq!(outcome.changed, expected.changed, "{name}: {input:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_object_at_depth_one() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("map.json");
+        fs::write(
+            &path,
+            "{\"b\": {\"y\": [1,2], \"x\": {}}, \"a\": 1, \"c\": \"\\/\", \"a\": 2}\n",
+        )
+        .unwrap();
+
+        let sort = SortOptions {
+            depth: Some(1),
+            ..SortOptions::default()
+        };
+        let options = FormatOptions::new().indent(2).sort(sort.clone());
+        let (output, outcome) = stream(&path, &options).unwrap();
+        let expected = format_file(&path, &options).unwrap();
+        assert_eq!(output, expected.output);
+        assert_eq!(outcome.duplicates, expected.duplicates);
+        assert_eq!(
+            output,
+            "{\n  \"a\": 1,\n  \"a\": 2,\n  \"b\": {\n    \"y\": [\n      1,\n      2\n    ],\n    \"x\": {}\n  },\n  \"c\": \"\\/\"\n}\n"
+        );
+        assert_eq!(outcome.duplicates[0].name, "a");
+        assert_eq!(outcome.duplicates[0].column, 49);
+
+        fs::write(&path, &output).unwrap();
+        assert!(!stream(&path, &options).unwrap().1.changed);
+
+        let options = FormatOptions::new().duplicate_keys(DuplicateKeys::Error);
+        assert!(matches!(
+            stream(&path, &options.sort(sort)),
+            Err(Error::DuplicateKeys(_))
+        ));
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.json");
+
+        fs::write(&path, "{\"a\": 1}").unwrap();
+        assert!(matches!(
+            stream(&path, &FormatOptions::new()),
+            Err(Error::Stream(_))
+        ));
+
+        fs::write(&path, "[1, // one\n 2]").unwrap();
+        assert!(matches!(
+            stream(&path, &FormatOptions::new()),
+            Err(Error::Stream(_))
+        ));
+
+        let sort = SortOptions {
+            sort_arrays: Some(ArrayOrder::Asc),
+            ..SortOptions::default()
+        };
+        fs::write(&path, "[2, 1]").unwrap();
+        assert!(matches!(
+            stream(&path, &FormatOptions::new().sort(sort)),
+            Err(Error::Stream(_))
+        ));
+
+        fs::write(&path, "[\n  {\"a\": 1},\n  {\"b\": tru}\n]").unwrap();
+        match stream(&path, &FormatOptions::new()) {
+            Err(Error::Parse(err)) => assert_eq!((err.line, err.column), (3, 9)),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        fs::write(&path, "[1] 2").unwrap();
+        assert!(matches!(
+            stream(&path, &FormatOptions::new()),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_stream_json_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("events.jsonl");
+        fs::write(&path, "{\"b\": 1, \"a\": 2}\n\n{\"a\": 1, \"a\": 2}\n").unwrap();
+
+        let (output, outcome) = stream(&path, &FormatOptions::new()).unwrap();
+        assert_eq!(output, "{\"a\":2,\"b\":1}\n\n{\"a\":1,\"a\":2}\n");
+        assert!(outcome.changed);
+        assert_eq!(outcome.duplicates[0].line, 3);
+    }
+
+    #[test]
+    fn test_stream_reports_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("big.json");
+        let record = "{\"b\": 1, \"a\": \"some text to fill the file\"}";
+        let input = format!("[{}]", vec![record; 60_000].join(", "));
+        fs::write(&path, &input).unwrap();
+
+        let mut reports = Vec::new();
+        format_stream(
+            &path,
+            std::io::sink(),
+            &FormatOptions::new(),
+            |read, total| reports.push((read, total)),
+        )
+        .unwrap();
+        assert!(reports.len() >= 2);
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(reports
+            .iter()
+            .all(|&(read, total)| read <= total && total == input.len() as u64));
+    }
+}
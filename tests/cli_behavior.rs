@@ -40,6 +40,44 @@ fn test_check_mode_passes_when_file_is_formatted() -> Result<(), Box<dyn std::er
     Ok(())
 }
 
+#[test]
+fn test_check_with_diff_prints_unified_diff() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    common::create_file(&file_path, common::UNSORTED_JSON);
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--diff"], temp_path);
+    cmd.assert()
+        .code(2)
+        .stdout(predicate::str::contains("needs formatting"))
+        .stdout(predicate::str::contains(
+            "--- a/sample.json\n+++ b/sample.json",
+        ))
+        .stdout(predicate::str::contains("-    \"c\": 3,\n"))
+        .stdout(predicate::str::contains("+    \"a\": 1,\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_does_not_write() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    common::create_file(&file_path, common::UNSORTED_JSON);
+
+    let mut cmd = common::run_cli("**/*.json", &["--diff"], temp_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("@@ "))
+        .stdout(predicate::str::contains("Needs formatting").not());
+
+    assert_eq!(fs::read_to_string(&file_path)?, common::UNSORTED_JSON);
+
+    Ok(())
+}
+
 #[test]
 fn test_stdout_prints_sorted_json_without_writing() -> Result<(), Box<dyn std::error::Error>> {
     let temp_dir = common::setup_test_directory();
@@ -130,6 +168,84 @@ fn test_stdin_mode_reads_and_writes_stdout() {
         .stdout(predicate::str::contains("\"a\": 1"));
 }
 
+#[test]
+fn test_stdin_output_keeps_its_line_endings() {
+    let temp_dir = common::setup_test_directory();
+    for (input, expected) in [
+        ("{\"b\":1,\"a\":2}\n", "{\n \"a\": 2,\n \"b\": 1\n}\n"),
+        (
+            "{\r\n  \"b\": 1,\r\n  \"a\": 2\r\n}\r\n",
+            "{\r\n  \"a\": 2,\r\n  \"b\": 1\r\n}\r\n",
+        ),
+        ("[1]", "[\n 1\n]\n"),
+    ] {
+        let mut cmd = Command::cargo_bin("tidy-json").expect("binary should build");
+        cmd.current_dir(temp_dir.path()).arg("--stdin");
+        let output = cmd.write_stdin(input).output().expect("binary should run");
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_stdin_jsonl_formats_each_line() {
+    let temp_dir = common::setup_test_directory();
+    let mut cmd = Command::cargo_bin("tidy-json").expect("binary should build");
+    cmd.current_dir(temp_dir.path())
+        .args(["--stdin", "--jsonl"]);
+    cmd.write_stdin("{\"b\": 1, \"a\": 2}\n\n[3, {\"d\": 4, \"c\": 5}]\n")
+        .assert()
+        .success()
+        .stdout("{\"a\":2,\"b\":1}\n\n[3,{\"c\":5,\"d\":4}]\n");
+
+    let mut cmd = Command::cargo_bin("tidy-json").expect("binary should build");
+    cmd.current_dir(temp_dir.path())
+        .args(["--stdin", "--jsonl"]);
+    cmd.write_stdin("{}\n{\"a\": }\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 2 column 7"));
+}
+
+#[test]
+fn test_config_enables_stdin_jsonl() {
+    let temp_dir = common::setup_test_directory();
+    common::create_file(
+        &temp_dir.path().join(".tidy-json.toml"),
+        "stdin = true\njsonl = true\n",
+    );
+
+    let mut cmd = Command::cargo_bin("tidy-json").expect("binary should build");
+    cmd.current_dir(temp_dir.path());
+    cmd.write_stdin("{\"b\": 1, \"a\": 2}\n[2, 1]\n")
+        .assert()
+        .success()
+        .stdout("{\"a\":2,\"b\":1}\n[2,1]\n");
+}
+
+#[test]
+fn test_jsonl_files_are_formatted_line_by_line() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("events.jsonl");
+    common::create_file(
+        &file_path,
+        "{\"type\": \"click\", \"at\": 1}\n{\"type\": \"view\", \"at\": 2}\n",
+    );
+
+    let mut cmd = common::run_cli("**/*.jsonl", &["--check"], temp_path);
+    cmd.assert().failure();
+
+    let mut cmd = common::run_cli("**/*.jsonl", &["--write"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\"at\":1,\"type\":\"click\"}\n{\"at\":2,\"type\":\"view\"}\n"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_config_file_defaults_are_applied() -> Result<(), Box<dyn std::error::Error>> {
     let temp_dir = common::setup_test_directory();
@@ -181,3 +297,494 @@ fn test_trailing_comma_is_parsed_without_panic() -> Result<(), Box<dyn std::erro
 
     Ok(())
 }
+
+#[test]
+fn test_json_reporter_lists_files_and_parse_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(&temp_path.join("sample.json"), common::UNSORTED_JSON);
+    common::create_file(&temp_path.join("broken.json"), "{\n  \"a\": \n}");
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--reporter=json"], temp_path);
+    let output = cmd.assert().code(2).get_output().stdout.clone();
+
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    let files = report["files"].as_array().expect("files should be listed");
+    let file = |name: &str| files.iter().find(|file| file["path"] == name).unwrap();
+    assert_eq!(file("sample.json")["status"], "needs-formatting");
+    assert_eq!(file("broken.json")["status"], "error");
+    assert_eq!(file("broken.json")["error"]["line"], 3);
+    assert_eq!(file("broken.json")["error"]["column"], 1);
+    assert_eq!(report["summary"]["total"], 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_reporters_list_every_duplicate_key() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(
+        &temp_path.join("sample.json"),
+        "{\n \"a\": 1,\n \"a\": 2,\n \"b\": 1,\n \"b\": 2\n}",
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--check"], temp_path);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "duplicate key `a` at line 3 column 2, duplicate key `b` at line 5 column 2",
+    ));
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--reporter=json"], temp_path);
+    let output = cmd.assert().code(2).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    let errors = &report["files"][0]["errors"];
+    assert_eq!(errors[0]["line"], 3);
+    assert_eq!(errors[1]["message"], "duplicate key `b`");
+    assert_eq!(errors[1]["line"], 5);
+
+    // Kept duplicates are reported as warnings.
+    let mut cmd = common::run_cli("**/*.json", &["--reporter=github"], temp_path);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "::warning file=sample.json,line=5,col=2,title=tidy-json::duplicate key `b`",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_codeclimate_reporter_lists_issues() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(&temp_path.join("sample.json"), common::UNSORTED_JSON);
+    common::create_file(&temp_path.join("broken.json"), "{\n  \"a\": \n}");
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--check", "--reporter=codeclimate"],
+        temp_path,
+    );
+    let output = cmd.assert().code(2).get_output().stdout.clone();
+
+    let issues: serde_json::Value = serde_json::from_slice(&output)?;
+    let issues = issues.as_array().expect("issues should be an array");
+    let issue = |name: &str| {
+        issues
+            .iter()
+            .find(|issue| issue["location"]["path"] == name)
+            .unwrap()
+    };
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issue("sample.json")["check_name"], "needs-formatting");
+    assert_eq!(issue("broken.json")["check_name"], "parse-error");
+    assert_eq!(issue("broken.json")["severity"], "major");
+    assert_eq!(issue("broken.json")["location"]["lines"]["begin"], 3);
+    assert!(issue("broken.json")["fingerprint"].is_string());
+
+    Ok(())
+}
+
+#[test]
+fn test_github_reporter_prints_annotations() {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(&temp_path.join("sample.json"), common::UNSORTED_JSON);
+
+    let mut cmd = common::run_cli("**/*.json", &["--reporter=github"], temp_path);
+    cmd.assert().success().stdout(predicate::eq(
+        "::warning file=sample.json,title=tidy-json::File is not formatted\n",
+    ));
+}
+
+#[test]
+fn test_number_literals_are_written_unchanged() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    common::create_file(
+        &file_path,
+        r#"{"total": 1234567890.12345678901234567890, "rate": 1.10, "big": 18446744073709551616, "exp": 1E-7}"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--indent=2"], temp_path);
+    cmd.assert().success();
+
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        r#"{
+  "big": 18446744073709551616,
+  "exp": 1E-7,
+  "rate": 1.10,
+  "total": 1234567890.12345678901234567890
+}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_json5_files_keep_json5_syntax() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("config.json5");
+    common::create_file(&file_path, "{ver: 0x10, 'name': 'app', // comment\n}\n");
+
+    let mut cmd = common::run_cli("**/*.json5", &["--write", "--indent=2"], temp_path);
+    cmd.assert().success();
+
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  'name': 'app', // comment\n  ver: 0x10\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_check_respects_preserved_trailing_commas() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("settings.jsonc");
+    common::create_file(&file_path, "{\n  \"b\": 1,\n  \"a\": 2,\n}");
+
+    let mut cmd = common::run_cli(
+        "**/*.jsonc",
+        &["--write", "--trailing-commas=preserve"],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  \"a\": 2,\n  \"b\": 1,\n}"
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.jsonc",
+        &["--check", "--trailing-commas=preserve"],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    let mut cmd = common::run_cli("**/*.jsonc", &["--check"], temp_path);
+    cmd.assert().failure();
+
+    // Plain JSON files never get trailing commas.
+    let json_path = temp_path.join("plain.json");
+    common::create_file(&json_path, "{\"b\": 1, \"a\": 2}");
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--stdout", "--trailing-commas=always", "--indent=2"],
+        temp_path,
+    );
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("{\n  \"a\": 2,\n  \"b\": 1\n}"));
+
+    Ok(())
+}
+
+#[test]
+fn test_line_endings_and_final_newline_are_kept() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let crlf_path = temp_path.join("crlf.json");
+    let lf_path = temp_path.join("lf.json");
+    common::create_file(&crlf_path, "{\r\n  \"b\": 1,\r\n  \"a\": 2\r\n}\r\n");
+    common::create_file(&lf_path, "{\n  \"a\": 1\n}\n");
+
+    let mut cmd = common::run_cli("**/*.json", &["--write"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&crlf_path)?,
+        "{\r\n  \"a\": 2,\r\n  \"b\": 1\r\n}\r\n"
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--check"], temp_path);
+    cmd.assert().success();
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &[
+            "--write",
+            "--end-of-line=lf",
+            "--insert-final-newline=false",
+        ],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&crlf_path)?,
+        "{\n  \"a\": 2,\n  \"b\": 1\n}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_editorconfig_sets_indentation() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    common::create_file(
+        &temp_path.join(".editorconfig"),
+        "root = true\n\n[*.json]\nindent_style = tab\ninsert_final_newline = true\n",
+    );
+    common::create_file(&file_path, r#"{"b": 1, "a": 2}"#);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n\t\"a\": 2,\n\t\"b\": 1\n}\n"
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--indent=2"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  \"a\": 2,\n  \"b\": 1\n}\n"
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--check", "--no-editorconfig", "--indent=2"],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_print_width_keeps_short_arrays_inline() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("shapes.json");
+    common::create_file(
+        &file_path,
+        r#"{"kind": "line", "coordinates": [[0.5, 1.25], [2, 3]]}"#,
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--indent=2", "--print-width=40"],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  \"coordinates\": [[0.5, 1.25], [2, 3]],\n  \"kind\": \"line\"\n}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_minify_writes_compact_json() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("payload.json");
+    common::create_file(
+        &file_path,
+        "{\n  \"b\": [1, 2],\n  \"a\": {\"c\": \"d e\"}\n}",
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--minify"], temp_path);
+    cmd.assert().failure();
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--minify"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        r#"{"a":{"c":"d e"},"b":[1,2]}"#
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--minify"], temp_path);
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_canonical_jcs_output() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("signed.json");
+    common::create_file(
+        &file_path,
+        "{\n  \"\u{1F600}\": 1.50,\n  \"\u{FB33}\": 1E3,\n  \"b\": [\"\\u0041\", 2, 1]\n}\n",
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--canonical=jcs", "--order=desc"],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\"b\":[\"A\",2,1],\"\u{1F600}\":1.5,\"\u{FB33}\":1000}"
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--canonical=jcs"], temp_path);
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn test_string_escapes_are_kept_unless_ascii_only() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    common::create_file(
+        &file_path,
+        r#"{"url": "https:\/\/example.com", "name": "Caf\u00e9 Zoë"}"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--indent=2"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  \"name\": \"Caf\\u00e9 Zoë\",\n  \"url\": \"https:\\/\\/example.com\"\n}"
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--ascii-only"], temp_path);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "{\n  \"name\": \"Caf\\u00e9 Zo\\u00eb\",\n  \"url\": \"https:\\/\\/example.com\"\n}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_formats_large_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("records.json");
+    common::create_file(&file_path, "[{\"b\": 1, \"a\": [2, 1]}, {}]\n");
+
+    let mut cmd = common::run_cli("**/*.json", &["--check", "--stream"], temp_path);
+    cmd.assert().failure();
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--stream", "--indent=2"],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(&file_path)?,
+        "[\n  {\n    \"a\": [\n      2,\n      1\n    ],\n    \"b\": 1\n  },\n  {}\n]\n"
+    );
+    assert!(!temp_path.join("records.tidy-json.tmp").exists());
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--check", "--stream", "--indent=2"],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    common::create_file(&file_path, "{\"b\": 1, \"a\": 2}");
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--stream"], temp_path);
+    cmd.assert()
+        .stderr(predicate::str::contains("Streaming does not support"));
+    assert_eq!(fs::read_to_string(&file_path)?, "{\"b\": 1, \"a\": 2}");
+    assert!(!temp_path.join("records.tidy-json.tmp").exists());
+
+    let mut cmd = common::run_cli("**/*.json", &["--stream", "--stdout"], temp_path);
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_matches_normal_output() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let record = r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": {"x": 1, "y": 2, "z": 3}}"#;
+    let content = format!("[{}]\n", [record; 8].join(", "));
+    let normal_path = temp_path.join("normal.json");
+    let streamed_path = temp_path.join("streamed.json");
+    common::create_file(&normal_path, &content);
+    common::create_file(&streamed_path, &content);
+
+    let flags = ["--write", "--order=random", "--seed=42", "--indent=2"];
+    let mut cmd = common::run_cli("**/normal.json", &flags, temp_path);
+    cmd.assert().success();
+    let mut cmd = common::run_cli(
+        "**/streamed.json",
+        &[&flags[..], &["--stream"]].concat(),
+        temp_path,
+    );
+    cmd.assert().success();
+
+    assert_ne!(fs::read_to_string(&normal_path)?, content);
+    assert_eq!(
+        fs::read_to_string(&streamed_path)?,
+        fs::read_to_string(&normal_path)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_deeply_nested_file_is_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let nested_path = temp_path.join("nested.json");
+    let other_path = temp_path.join("other.json");
+    common::create_file(
+        &nested_path,
+        &format!("{}{}", "[".repeat(100_000), "]".repeat(100_000)),
+    );
+    common::create_file(&other_path, r#"{"b": 1, "a": 2}"#);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write"], temp_path);
+    cmd.assert().stderr(predicate::str::contains(
+        "nesting too deep at line 1 column 129",
+    ));
+    assert_eq!(
+        fs::read_to_string(&other_path)?,
+        "{\n \"a\": 2,\n \"b\": 1\n}"
+    );
+
+    let mut cmd = common::run_cli("**/nested.json", &["--check", "--stream"], temp_path);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("nesting too deep"));
+
+    Ok(())
+}
+
+#[test]
+fn test_check_fails_on_duplicate_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+    let content = "{\n \"a\": 1,\n \"a\": 2\n}";
+    common::create_file(&file_path, content);
+
+    let mut cmd = common::run_cli("**/*.json", &["--check"], temp_path);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "duplicate key `a` at line 3 column 2",
+    ));
+
+    let mut cmd = common::run_cli("**/*.json", &["--write"], temp_path);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "duplicate key `a` at line 3 column 2",
+    ));
+    assert_eq!(fs::read_to_string(&file_path)?, content);
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--duplicate-keys=keep-last"],
+        temp_path,
+    );
+    cmd.assert().success();
+    assert_eq!(fs::read_to_string(&file_path)?, "{\n \"a\": 2\n}");
+
+    Ok(())
+}
@@ -87,7 +87,6 @@ fn test_jsonc_comment_output_snapshot() -> Result<(), Box<dyn std::error::Error>
     cmd.assert().success();
 
     let content = fs::read_to_string(&file_path)?;
-    // Current JSONC parsing normalizes to JSON and strips comments.
     assert_snapshot!(content);
 
     Ok(())
@@ -126,3 +125,48 @@ fn test_jsonc_comment_moves_with_sorted_key() -> Result<(), Box<dyn std::error::
 
     Ok(())
 }
+
+#[test]
+fn test_jsonc_comments_survive_sorting_anywhere() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("settings.jsonc");
+
+    common::create_file(
+        &file_path,
+        r#"{
+  "z": [
+    2, // two
+    // one
+    1
+  ],
+  "b": true, // after b
+  /* before a */
+  "a": null
+  // before closing brace
+}
+"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.jsonc", &["--write"], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    assert_eq!(
+        content,
+        r#"{
+  /* before a */
+  "a": null,
+  "b": true, // after b
+  "z": [
+    2, // two
+    // one
+    1
+  ]
+  // before closing brace
+}
+"#
+    );
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 use std::fs;
 
 pub mod common;
@@ -34,3 +35,339 @@ fn test_line_length_order_sorts_keys_by_key_length() -> Result<(), Box<dyn std::
 
     Ok(())
 }
+
+#[test]
+fn test_first_and_last_pin_keys_around_sorted_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("package.json");
+
+    common::create_file(
+        &file_path,
+        r#"{
+  "scripts": {},
+  "author": "me",
+  "version": "1.0.0",
+  "name": "pkg"
+}"#,
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--first", "name,version", "--last=scripts"],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    assert_eq!(
+        content,
+        r#"{
+  "name": "pkg",
+  "version": "1.0.0",
+  "author": "me",
+  "scripts": {}
+}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_first_and_last_are_read_from_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("openapi.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"paths": {}, "info": {}, "openapi": "3.1.0"}"#,
+    );
+    common::create_file(
+        &temp_path.join(".tidy-json.toml"),
+        r#"write = true
+first = ["openapi"]
+last = ["info"]
+"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &[], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    let openapi = content.find("\"openapi\"").expect("openapi key missing");
+    let paths = content.find("\"paths\"").expect("paths key missing");
+    let info = content.find("\"info\"").expect("info key missing");
+
+    assert!(openapi < paths);
+    assert!(paths < info);
+
+    Ok(())
+}
+
+#[test]
+fn test_natural_order_compares_digit_runs_numerically() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+
+    common::create_file(&file_path, r#"{"item10": 3, "item2": 2, "item1": 1}"#);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--order=natural-asc"], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    let item1 = content.find("\"item1\"").expect("item1 key missing");
+    let item2 = content.find("\"item2\"").expect("item2 key missing");
+    let item10 = content.find("\"item10\"").expect("item10 key missing");
+
+    assert!(item1 < item2);
+    assert!(item2 < item10);
+
+    Ok(())
+}
+
+#[test]
+fn test_collation_orders_keys_case_insensitively() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("messages.json");
+
+    common::create_file(&file_path, r#"{"Zebra": 1, "apple": 2, "Éclair": 3}"#);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--locale=en"], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    let apple = content.find("\"apple\"").expect("apple key missing");
+    let eclair = content.find("\"Éclair\"").expect("Éclair key missing");
+    let zebra = content.find("\"Zebra\"").expect("Zebra key missing");
+
+    assert!(apple < eclair);
+    assert!(eclair < zebra);
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_locale_is_rejected() {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(&temp_path.join("sample.json"), common::UNSORTED_JSON);
+
+    let mut cmd = common::run_cli("**/*.json", &["--locale=not a locale"], temp_path);
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_unknown_locale_language_is_rejected() {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    common::create_file(&temp_path.join("sample.json"), common::UNSORTED_JSON);
+
+    let mut cmd = common::run_cli("**/*.json", &["--locale=dee"], temp_path);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unsupported locale dee: unknown language `dee`",
+    ));
+
+    common::create_file(&temp_path.join(".tidy-json.toml"), "locale = \"dee\"\n");
+    let mut cmd = common::run_cli("**/*.json", &[], temp_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unsupported locale dee"));
+}
+
+#[test]
+fn test_config_rules_override_order_per_path() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("package.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"scripts": {"test": "t", "build": "b"}, "dependencies": {"b": "1", "a": "2"}}"#,
+    );
+    common::create_file(
+        &temp_path.join(".tidy-json.toml"),
+        r#"write = true
+
+[[rules]]
+path = "/scripts"
+order = "none"
+"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &[], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    assert_eq!(
+        content,
+        r#"{
+ "dependencies": {
+  "a": "2",
+  "b": "1"
+ },
+ "scripts": {
+  "test": "t",
+  "build": "b"
+ }
+}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_auto_preset_is_picked_by_file_name() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let package_path = temp_path.join("package.json");
+    let other_path = temp_path.join("other.json");
+
+    let content = r#"{"scripts": {"test": "t", "build": "b"}, "version": "1.0.0", "name": "pkg"}"#;
+    common::create_file(&package_path, content);
+    common::create_file(&other_path, content);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--preset=auto"], temp_path);
+    cmd.assert().success();
+
+    let package = fs::read_to_string(&package_path)?;
+    assert!(package.find("\"name\"") < package.find("\"version\""));
+    assert!(package.find("\"version\"") < package.find("\"scripts\""));
+    assert!(package.find("\"test\"") < package.find("\"build\""));
+
+    let other = fs::read_to_string(&other_path)?;
+    assert!(other.find("\"name\"") < other.find("\"scripts\""));
+    assert!(other.find("\"scripts\"") < other.find("\"version\""));
+    assert!(other.find("\"build\"") < other.find("\"test\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_auto_preset_walks_into_eslintrc() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let eslintrc_path = temp_path.join(".eslintrc.json");
+    let hidden_path = temp_path.join(".hidden.json");
+
+    let content = r#"{"rules": {"semi": "error"}, "extends": "eslint:recommended", "root": true}"#;
+    common::create_file(&eslintrc_path, content);
+    common::create_file(&hidden_path, content);
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--preset=auto"], temp_path);
+    cmd.assert().success();
+
+    let eslintrc = fs::read_to_string(&eslintrc_path)?;
+    assert!(eslintrc.find("\"root\"") < eslintrc.find("\"extends\""));
+    assert!(eslintrc.find("\"extends\"") < eslintrc.find("\"rules\""));
+
+    assert_eq!(fs::read_to_string(&hidden_path)?, content);
+
+    Ok(())
+}
+
+#[test]
+fn test_sort_arrays_and_dedupe_from_cli() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"files": ["src", "dist", "src"], "users": [{"id": 2}, {"id": 1}]}"#,
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &[
+            "--write",
+            "--sort-arrays=asc",
+            "--sort-arrays-by=id",
+            "--dedupe-arrays",
+        ],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    let content: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file_path)?)?;
+    assert_eq!(
+        content,
+        serde_json::json!({"files": ["dist", "src"], "users": [{"id": 1}, {"id": 2}]})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_value_order_sorts_members_by_value() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"search": {"priority": 3}, "export": {"priority": 1}, "import": {"priority": 2}}"#,
+    );
+
+    let mut cmd = common::run_cli(
+        "**/*.json",
+        &["--write", "--order=value-desc", "--order-by=priority"],
+        temp_path,
+    );
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    assert!(content.find("\"search\"") < content.find("\"import\""));
+    assert!(content.find("\"import\"") < content.find("\"export\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_random_order_is_reproducible_with_seed() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6, "g": 7, "h": 8}"#,
+    );
+
+    let shuffle = || -> Result<String, Box<dyn std::error::Error>> {
+        let mut cmd = common::run_cli(
+            "**/*.json",
+            &["--stdout", "--order=rand", "--seed=1234"],
+            temp_path,
+        );
+        let output = cmd.assert().success().get_output().stdout.clone();
+        Ok(String::from_utf8(output)?)
+    };
+
+    assert_eq!(shuffle()?, shuffle()?);
+
+    Ok(())
+}
+
+#[test]
+fn test_line_length_order_measures_rendered_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::setup_test_directory();
+    let temp_path = temp_dir.path();
+    let file_path = temp_path.join("sample.json");
+
+    common::create_file(
+        &file_path,
+        r#"{"a": "a long string value", "bbb": 1, "cc": {"nested": true}}"#,
+    );
+
+    let mut cmd = common::run_cli("**/*.json", &["--write", "--order=line-length"], temp_path);
+    cmd.assert().success();
+
+    let content = fs::read_to_string(&file_path)?;
+    assert!(content.find("\"cc\"") < content.find("\"bbb\""));
+    assert!(content.find("\"bbb\"") < content.find("\"a\""));
+
+    Ok(())
+}